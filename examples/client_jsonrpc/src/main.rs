@@ -1,21 +1,18 @@
-use example_server_lib::MyResult;
-use jsonrpsee::core::client::ClientT;
+use example_server_lib::greeter_protocols::GreeterRpcClient;
 use jsonrpsee::http_client::HttpClient;
-use jsonrpsee::rpc_params;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let server_addr = "127.0.0.1:9003";
     let url = format!("http://{}", server_addr);
 
+    // `GreeterRpcClient` is implemented for every jsonrpsee client, HTTP and WebSocket alike.
     let client = HttpClient::builder().build(url)?;
-    let params = rpc_params!["Jimmy"];
-    let response: MyResult = client.request("greet", params).await?;
+    let response = client.greet("Jimmy".to_string()).await?;
     println!("✅ JSON-RPC Response: {:?}", response);
 
     // Call the 'update_settings' method
-    let settings_params = rpc_params![101, 85, "dark"];
-    let settings_response: MyResult = client.request("update_settings", settings_params).await?;
+    let settings_response = client.update_settings(101, 85, "dark".to_string()).await?;
     println!("✅ JSON-RPC Settings Response: {:?}", settings_response);
 
     Ok(())
@@ -1,4 +1,4 @@
-use example_server_lib::GreeterClient;
+use example_server_lib::greeter_protocols::GreeterClient;
 use tarpc::client;
 use tarpc::context;
 use tarpc::tokio_serde::formats::Json;
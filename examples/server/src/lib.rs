@@ -8,7 +8,8 @@ use multi_rpc::*;
 pub struct MyResult(pub Result<String, RpcError>);
 
 #[multi_rpc_trait]
-trait Greeter {
+#[allow(async_fn_in_trait)]
+pub trait Greeter {
     /// A simple method that takes a path parameter.
     async fn greet(&self, name: String) -> MyResult;
 
@@ -0,0 +1,176 @@
+use proc_macro2::TokenStream;
+use proc_macro2::TokenTree;
+use quote::quote;
+use quote::ToTokens;
+use syn::FnArg;
+use syn::GenericParam;
+use syn::Ident;
+use syn::ImplItem;
+use syn::ItemImpl;
+use syn::Pat;
+use syn::ReturnType;
+use syn::Type;
+
+use crate::dispatch::is_blocking;
+use crate::dispatch::variant_ident;
+use crate::protocols::is_context_arg;
+
+/// Generates the request message enum and the `Actor` impl for `#[multi_rpc_impl(actor)]`.
+///
+/// Every method becomes a variant carrying the method's arguments and a oneshot sender for its
+/// return value. The actor task receives the messages in order and invokes the service methods
+/// one at a time. A `&RequestContext` argument is carried by value.
+///
+/// The message enum and the handle carrying it aren't generic, so the argument and return types
+/// can't use the generic parameters of the `impl` block.
+pub fn generate(item_impl: &ItemImpl, message_ident: &Ident) -> syn::Result<TokenStream> {
+    let self_ty = &item_impl.self_ty;
+    // The actor future holds `&self` across `.await` points, so it's only `Send` if the service
+    // is `Sync`.
+    let mut generics = item_impl.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote! { #self_ty: Send + Sync + 'static });
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    let mut variants = Vec::new();
+    let mut arms = Vec::new();
+    let mut errors: Option<syn::Error> = None;
+    let mut push_error = |err: syn::Error| match &mut errors {
+        Some(errors) => errors.combine(err),
+        None => errors = Some(err),
+    };
+    let generic_params: Vec<Ident> = item_impl
+        .generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Type(param) => param.ident.clone(),
+            GenericParam::Lifetime(param) => param.lifetime.ident.clone(),
+            GenericParam::Const(param) => param.ident.clone(),
+        })
+        .collect();
+    for item in &item_impl.items {
+        let ImplItem::Fn(method) = item else {
+            continue;
+        };
+        let method_ident = &method.sig.ident;
+        let variant_ident = variant_ident(method);
+
+        let mut fields = Vec::new();
+        let mut field_tys = Vec::new();
+        let mut call_args = Vec::new();
+        for arg in &method.sig.inputs {
+            if let FnArg::Typed(pt) = arg {
+                match &*pt.pat {
+                    Pat::Ident(pi) => {
+                        let field = &pi.ident;
+                        fields.push(field);
+                        match &*pt.ty {
+                            Type::Reference(reference) if is_context_arg(arg) => {
+                                field_tys.push(&*reference.elem);
+                                call_args.push(quote! { &#field });
+                            }
+                            ty => {
+                                field_tys.push(ty);
+                                call_args.push(quote! { #field });
+                            }
+                        }
+                    }
+                    pat => push_error(syn::Error::new_spanned(
+                        pat,
+                        "arguments must be plain identifiers in actor mode",
+                    )),
+                }
+            }
+        }
+
+        let output = match &method.sig.output {
+            ReturnType::Default => quote! { () },
+            ReturnType::Type(_, ty) => quote! { #ty },
+        };
+
+        let output_ty = match &method.sig.output {
+            ReturnType::Default => None,
+            ReturnType::Type(_, ty) => Some(&**ty),
+        };
+        for ty in field_tys.iter().copied().chain(output_ty) {
+            if let Some(param) = generic_param_in(ty.to_token_stream(), &generic_params) {
+                push_error(syn::Error::new_spanned(
+                    ty,
+                    format!(
+                        "actor mode can't carry the generic parameter `{}` of the impl in its messages",
+                        param
+                    ),
+                ));
+            }
+        }
+
+        variants.push(quote! {
+            #variant_ident {
+                #(#fields: #field_tys,)*
+                reply: tokio::sync::oneshot::Sender<#output>,
+            }
+        });
+
+        let arm = if is_blocking(method) {
+            // The service is moved to the blocking pool for the call and handed back afterwards.
+            quote! {
+                match multi_rpc::actor::respond_blocking(self, reply, move |service| {
+                    service.#method_ident(#(#call_args),*)
+                })
+                .await
+                {
+                    Some(service) => self = service,
+                    None => return,
+                }
+            }
+        } else if method.sig.asyncness.is_some() {
+            quote! {
+                multi_rpc::actor::respond(reply, async { self.#method_ident(#(#call_args),*).await }).await
+            }
+        } else {
+            quote! {
+                multi_rpc::actor::respond(reply, async { self.#method_ident(#(#call_args),*) }).await
+            }
+        };
+
+        arms.push(quote! {
+            #message_ident::#variant_ident { #(#fields,)* reply } => {
+                #arm
+            }
+        });
+    }
+
+    if let Some(err) = errors {
+        return Err(err);
+    }
+
+    Ok(quote! {
+        /// A request to the service, sent by the protocol adapters to the actor task.
+        pub enum #message_ident {
+            #(#variants),*
+        }
+
+        impl #impl_generics multi_rpc::actor::Actor<#message_ident> for #self_ty #where_clause {
+            #[allow(unused_mut)]
+            async fn run(mut self, mut requests: tokio::sync::mpsc::Receiver<#message_ident>) {
+                while let Some(request) = requests.recv().await {
+                    match request {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+    })
+}
+
+// The first of `params` named in `tokens`, looking into groups.
+fn generic_param_in(tokens: TokenStream, params: &[Ident]) -> Option<&Ident> {
+    tokens.into_iter().find_map(|token| match token {
+        TokenTree::Ident(ident) => params.iter().find(|param| **param == ident),
+        TokenTree::Group(group) => generic_param_in(group.stream(), params),
+        _ => None,
+    })
+}
@@ -0,0 +1,141 @@
+use proc_macro2::Span;
+use syn::parse::Parse;
+use syn::parse::ParseStream;
+use syn::punctuated::Punctuated;
+use syn::Ident;
+use syn::Result;
+use syn::Token;
+use syn::Visibility;
+
+use crate::protocols::Protocol;
+use crate::PROTOCOLS;
+
+// Arguments shared by `#[multi_rpc_trait(...)]` and `#[multi_rpc_impl(...)]`.
+//
+// `protocols(tarpc, jsonrpsee)` restricts code generation to the listed protocols.
+// When it is omitted, every protocol enabled through cargo features is generated.
+//
+// `actor` (impl only) serves the service from a dedicated task that owns it, instead of
+// sharing it behind a lock.
+//
+// `remote` (trait only) generates a `{Trait}Remote` client that implements the trait itself.
+//
+// A leading visibility such as `pub(crate)` (impl only) sets the visibility of the generated
+// impl module, which is public by default.
+#[derive(Default)]
+pub struct MacroArgs {
+    pub vis: Option<Visibility>,
+    protocols: Option<Punctuated<Ident, Token![,]>>,
+    pub actor: Option<syn::Path>,
+    pub remote: Option<syn::Path>,
+}
+
+impl Parse for MacroArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut args = MacroArgs::default();
+
+        if input.peek(Token![pub]) {
+            args.vis = Some(input.parse()?);
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        let top_level_vars = Punctuated::<syn::Meta, Token![,]>::parse_terminated(input)?;
+
+        for meta in top_level_vars {
+            if meta.path().is_ident("protocols") {
+                let list = meta.require_list()?;
+                if args.protocols.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        &list.path,
+                        "`protocols` may only be specified once",
+                    ));
+                }
+                args.protocols = Some(list.parse_args_with(Punctuated::parse_terminated)?);
+            } else if meta.path().is_ident("actor") || meta.path().is_ident("remote") {
+                let path = meta.require_path_only()?;
+                let slot = if path.is_ident("actor") {
+                    &mut args.actor
+                } else {
+                    &mut args.remote
+                };
+                if slot.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        path,
+                        format!("`{}` may only be specified once", path.get_ident().unwrap()),
+                    ));
+                }
+                *slot = Some(path.clone());
+            } else {
+                return Err(syn::Error::new_spanned(
+                    meta.path(),
+                    "unknown argument, expected a visibility, `protocols(...)`, `actor` or `remote`",
+                ));
+            }
+        }
+
+        Ok(args)
+    }
+}
+
+impl MacroArgs {
+    /// Resolves the protocols to generate code for.
+    ///
+    /// Fails if a listed protocol is unknown, listed twice, or its cargo feature is disabled.
+    pub fn protocols(&self) -> Result<Vec<&'static dyn Protocol>> {
+        let Some(names) = &self.protocols else {
+            return Ok(PROTOCOLS
+                .iter()
+                .copied()
+                .filter(|p| p.is_enabled())
+                .collect());
+        };
+
+        let mut selected: Vec<&'static dyn Protocol> = Vec::new();
+        for name in names {
+            let protocol = PROTOCOLS
+                .iter()
+                .copied()
+                .find(|p| name == p.name())
+                .ok_or_else(|| {
+                    let known: Vec<_> = PROTOCOLS
+                        .iter()
+                        .map(|p| format!("`{}`", p.name()))
+                        .collect();
+                    syn::Error::new_spanned(
+                        name,
+                        format!("unknown protocol, expected one of {}", known.join(", ")),
+                    )
+                })?;
+
+            if selected.iter().any(|p| p.name() == protocol.name()) {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "protocol is listed more than once",
+                ));
+            }
+            if !protocol.is_enabled() {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    format!(
+                        "protocol `{}` requires the `{}` feature of `multi-rpc` to be enabled",
+                        protocol.name(),
+                        protocol.feature()
+                    ),
+                ));
+            }
+            selected.push(protocol);
+        }
+
+        Ok(selected)
+    }
+
+    /// The span of `protocol` in `protocols(...)`, to point errors about it at the user's code.
+    pub fn protocol_span(&self, protocol: &dyn Protocol) -> Span {
+        self.protocols
+            .iter()
+            .flatten()
+            .find(|name| *name == protocol.name())
+            .map_or_else(Span::call_site, Ident::span)
+    }
+}
@@ -0,0 +1,175 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::FnArg;
+use syn::Ident;
+use syn::ImplItemFn;
+use syn::ItemImpl;
+use syn::Pat;
+
+use crate::naming::to_pascal_case;
+use crate::protocols::is_context_arg;
+
+/// Describes how the generated protocol adapters reach the user's service.
+#[cfg_attr(
+    not(any(feature = "tarpc", feature = "rest", feature = "jsonrpsee")),
+    allow(dead_code)
+)]
+pub enum Dispatch {
+    /// The service is shared by all servers behind an `Arc<RwLock<S>>`.
+    Shared,
+    /// The service is owned by a single actor task and receives `message_ident` requests
+    /// through an `ActorHandle`.
+    Actor { message_ident: Ident },
+}
+
+#[cfg_attr(
+    not(any(feature = "tarpc", feature = "rest", feature = "jsonrpsee")),
+    allow(dead_code)
+)]
+impl Dispatch {
+    /// The handle type that `ServerBuilder` passes to every server factory.
+    pub fn handle_ty(&self, item_impl: &ItemImpl) -> TokenStream {
+        match self {
+            Dispatch::Shared => {
+                let self_ty = &item_impl.self_ty;
+                quote! { std::sync::Arc<tokio::sync::RwLock<#self_ty>> }
+            }
+            Dispatch::Actor { message_ident } => {
+                quote! { multi_rpc::actor::ActorHandle<#message_ident> }
+            }
+        }
+    }
+
+    /// The generics for the adapter impls and server factories.
+    ///
+    /// In shared mode these are the generics of the user's `impl` block, with the service type
+    /// required to be `Send + Sync + 'static` so it can be shared across the spawned server
+    /// tasks. An actor handle doesn't mention the service type, so in actor mode there are none.
+    pub fn generics(&self, item_impl: &ItemImpl) -> syn::Generics {
+        match self {
+            Dispatch::Shared => {
+                let self_ty = &item_impl.self_ty;
+                let mut generics = item_impl.generics.clone();
+                generics
+                    .make_where_clause()
+                    .predicates
+                    .push(syn::parse_quote! { #self_ty: Send + Sync + 'static });
+                generics
+            }
+            Dispatch::Actor { .. } => syn::Generics::default(),
+        }
+    }
+
+    /// Generates the expression that invokes `method` through the handle in `service`.
+    ///
+    /// In shared mode, methods taking `&self` only acquire a read guard, so they run
+    /// concurrently; `&mut self` methods acquire the write guard. Async methods are awaited,
+    /// sync methods are called inline, and sync methods marked `#[blocking]` are moved onto
+    /// tokio's blocking thread pool so they don't stall the async workers.
+    ///
+    /// In actor mode, the arguments are sent to the actor task and the reply is awaited. If the
+    /// actor can't answer, `on_actor_error` runs with the `multi_rpc::actor::ActorError` bound
+    /// to `err`, and must leave the adapter, e.g. by returning an error response.
+    ///
+    /// `args` holds a value for every argument; the `RequestContext` is passed by value and
+    /// borrowed for the call.
+    pub fn call(
+        &self,
+        service: &TokenStream,
+        method: &ImplItemFn,
+        args: &[TokenStream],
+        on_actor_error: &TokenStream,
+    ) -> TokenStream {
+        let method_ident = &method.sig.ident;
+
+        if let Dispatch::Actor { message_ident } = self {
+            let variant_ident = variant_ident(method);
+            let fields = arg_idents(method);
+            return quote! {
+                match #service.call(|reply| #message_ident::#variant_ident {
+                    #(#fields: #args,)*
+                    reply,
+                }).await {
+                    Ok(value) => value,
+                    Err(err) => #on_actor_error,
+                }
+            };
+        }
+
+        let args: Vec<TokenStream> = method
+            .sig
+            .inputs
+            .iter()
+            .filter(|arg| matches!(arg, FnArg::Typed(_)))
+            .zip(args)
+            .map(|(arg, value)| {
+                if is_context_arg(arg) {
+                    quote! { &#value }
+                } else {
+                    value.clone()
+                }
+            })
+            .collect();
+
+        let is_mut = method
+            .sig
+            .receiver()
+            .is_some_and(|receiver| receiver.mutability.is_some());
+        let (lock, blocking_lock) = if is_mut {
+            (quote! { write }, quote! { blocking_write })
+        } else {
+            (quote! { read }, quote! { blocking_read })
+        };
+
+        if method.sig.asyncness.is_some() {
+            quote! { #service.#lock().await.#method_ident(#(#args),*).await }
+        } else if is_blocking(method) {
+            quote! {
+                {
+                    let service = #service.clone();
+                    match tokio::task::spawn_blocking(move || {
+                        service.#blocking_lock().#method_ident(#(#args),*)
+                    })
+                    .await
+                    {
+                        Ok(result) => result,
+                        Err(err) => std::panic::resume_unwind(err.into_panic()),
+                    }
+                }
+            }
+        } else {
+            quote! { #service.#lock().await.#method_ident(#(#args),*) }
+        }
+    }
+}
+
+/// Whether a method is marked `#[blocking]`.
+pub fn is_blocking(method: &ImplItemFn) -> bool {
+    method.attrs.iter().any(|a| a.path().is_ident("blocking"))
+}
+
+/// The actor message variant for `method`, e.g. `UpdateSettings` for `update_settings`.
+pub fn variant_ident(method: &ImplItemFn) -> Ident {
+    Ident::new(
+        &to_pascal_case(&method.sig.ident.to_string()),
+        method.sig.ident.span(),
+    )
+}
+
+/// The identifiers of the arguments of `method`, excluding the receiver.
+///
+/// Actor mode requires plain identifiers, which is checked when generating the actor.
+pub fn arg_idents(method: &ImplItemFn) -> Vec<&Ident> {
+    method
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pt) => match &*pt.pat {
+                Pat::Ident(pi) => Some(&pi.ident),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
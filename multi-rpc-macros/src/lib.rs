@@ -1,10 +1,24 @@
 use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::format_ident;
 use quote::quote;
 use syn::parse_macro_input;
+use syn::DeriveInput;
+use syn::ImplItem;
 use syn::ItemImpl;
 use syn::ItemTrait;
+use syn::TraitItem;
 
+mod actor;
+mod args;
+mod dispatch;
+mod naming;
 mod protocols;
+mod remote;
+mod rpc_error;
+use args::MacroArgs;
+use dispatch::Dispatch;
+use protocols::is_context_arg;
 use protocols::JsonRpSee;
 use protocols::Protocol;
 use protocols::RestAxum;
@@ -13,39 +27,241 @@ use protocols::Tarpc;
 const PROTOCOLS: &[&dyn Protocol] = &[&Tarpc, &RestAxum, &JsonRpSee];
 
 #[proc_macro_attribute]
-pub fn multi_rpc_trait(_attr: TokenStream, input: TokenStream) -> TokenStream {
+pub fn multi_rpc_trait(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as MacroArgs);
     let item_trait = parse_macro_input!(input as ItemTrait);
 
-    let generated_trait_code: Vec<_> = PROTOCOLS
+    if let Some(actor) = &args.actor {
+        return syn::Error::new_spanned(
+            actor,
+            "`actor` is chosen per implementation, use it on `#[multi_rpc_impl]` instead",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    if let Some(vis) = &args.vis {
+        return syn::Error::new_spanned(
+            vis,
+            "the generated trait module takes the visibility of the trait",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let protocols = match args.protocols() {
+        Ok(protocols) => protocols,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    if let Err(err) = validate_context_params(&item_trait) {
+        return err.to_compile_error().into();
+    }
+
+    let mut generated_trait_code =
+        match collect_generated(protocols.iter().map(|p| p.transform_trait(&item_trait))) {
+            Ok(code) => code,
+            Err(err) => return err.to_compile_error().into(),
+        };
+    if args.remote.is_some() {
+        let remote_code = std::iter::once(remote::generate(&item_trait))
+            .chain(protocols.iter().map(|p| p.transform_remote(&item_trait)));
+        match collect_generated(remote_code) {
+            Ok(code) => generated_trait_code.extend(code),
+            Err(err) => return err.to_compile_error().into(),
+        }
+    }
+
+    let vis = &item_trait.vis;
+    let module_ident = naming::trait_module_ident(&item_trait.ident);
+    let markers = protocols
         .iter()
-        .map(|p| p.transform_trait(&item_trait))
-        .collect();
+        .map(|p| naming::protocol_marker_ident(*p, Span::call_site()));
 
     quote! {
         #item_trait
-        #(#generated_trait_code)*
+
+        #vis mod #module_ident {
+            #[allow(unused_imports)]
+            use super::*;
+            #(#generated_trait_code)*
+            #(
+                #[doc(hidden)]
+                pub mod #markers {
+                    pub use super::*;
+                }
+            )*
+        }
     }
     .into()
 }
 
 #[proc_macro_attribute]
-pub fn multi_rpc_impl(_attr: TokenStream, input: TokenStream) -> TokenStream {
+pub fn multi_rpc_impl(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as MacroArgs);
     let item_impl = parse_macro_input!(input as ItemImpl);
 
-    let generated_impl_code: Vec<_> = PROTOCOLS
+    let Some((_, trait_path, _)) = &item_impl.trait_ else {
+        return syn::Error::new_spanned(
+            &item_impl.self_ty,
+            "#[multi_rpc_impl] must be applied to an `impl Trait for Type` block",
+        )
+        .to_compile_error()
+        .into();
+    };
+    if let Some(remote) = &args.remote {
+        return syn::Error::new_spanned(
+            remote,
+            "`remote` generates a client for the trait, use it on `#[multi_rpc_trait]` instead",
+        )
+        .to_compile_error()
+        .into();
+    }
+    if let Err(err) = validate_blocking_methods(&item_impl) {
+        return err.to_compile_error().into();
+    }
+
+    let trait_ident = &trait_path.segments.last().unwrap().ident;
+    let module_ident = match naming::impl_module_ident(trait_ident, &item_impl.self_ty) {
+        Ok(ident) => ident,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let module_vis = match &args.vis {
+        Some(vis) => quote! { #vis },
+        None => quote! { pub },
+    };
+    let trait_module_path = naming::trait_module_path(trait_path);
+
+    let protocols = match args.protocols() {
+        Ok(protocols) => protocols,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let dispatch = match args.actor {
+        Some(_) => Dispatch::Actor {
+            message_ident: format_ident!("{}Message", trait_ident),
+        },
+        None => Dispatch::Shared,
+    };
+    let actor_code = match &dispatch {
+        Dispatch::Actor { message_ident } => match actor::generate(&item_impl, message_ident) {
+            Ok(code) => code,
+            Err(err) => return err.to_compile_error().into(),
+        },
+        Dispatch::Shared => quote! {},
+    };
+
+    // Imports the marker module of every protocol from the trait module, so that a protocol
+    // the trait doesn't generate fails with a single error on the impl's `protocols(...)`.
+    let markers = protocols
         .iter()
-        .map(|p| p.transform_impl(&item_impl))
-        .collect();
+        .map(|p| naming::protocol_marker_ident(*p, args.protocol_span(*p)));
+
+    let generated_impl_code = match collect_generated(
+        protocols
+            .iter()
+            .map(|p| p.transform_impl(&item_impl, &dispatch)),
+    ) {
+        Ok(code) => code,
+        Err(err) => return err.to_compile_error().into(),
+    };
 
     quote! {
         #item_impl
 
-        #(#generated_impl_code)*
+        #module_vis mod #module_ident {
+            #[allow(unused_imports)]
+            use super::*;
+            #[allow(unused_imports)]
+            use #trait_module_path::*;
+            #(
+                #[allow(unused_imports)]
+                use #trait_module_path::#markers::*;
+            )*
+            #actor_code
+            #(#generated_impl_code)*
+        }
     }
     .into()
 }
 
+// Collects the code generated by each protocol, combining the errors of all of them
+// so that every problem is reported in a single compilation.
+fn collect_generated(
+    results: impl Iterator<Item = syn::Result<proc_macro2::TokenStream>>,
+) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    let mut code = Vec::new();
+    let mut errors: Option<syn::Error> = None;
+    for result in results {
+        match result {
+            Ok(tokens) => code.push(tokens),
+            Err(err) => match &mut errors {
+                Some(errors) => errors.combine(err),
+                None => errors = Some(err),
+            },
+        }
+    }
+    match errors {
+        Some(err) => Err(err),
+        None => Ok(code),
+    }
+}
+
+// `#[blocking]` moves a sync method onto the blocking thread pool, which an async method
+// has no use for.
+fn validate_blocking_methods(item_impl: &ItemImpl) -> syn::Result<()> {
+    for item in &item_impl.items {
+        if let ImplItem::Fn(method) = item {
+            let blocking = method.attrs.iter().find(|a| a.path().is_ident("blocking"));
+            if let (Some(attr), Some(asyncness)) = (blocking, &method.sig.asyncness) {
+                let mut err =
+                    syn::Error::new_spanned(attr, "`#[blocking]` can only be used on sync methods");
+                err.combine(syn::Error::new_spanned(
+                    asyncness,
+                    "remove `async` or `#[blocking]`",
+                ));
+                return Err(err);
+            }
+        }
+    }
+    Ok(())
+}
+
+// The adapters fill in a single `&RequestContext` per call.
+fn validate_context_params(item_trait: &ItemTrait) -> syn::Result<()> {
+    for item in &item_trait.items {
+        if let TraitItem::Fn(method) = item {
+            let mut context_args = method.sig.inputs.iter().filter(|arg| is_context_arg(arg));
+            if let (Some(_), Some(duplicate)) = (context_args.next(), context_args.next()) {
+                return Err(syn::Error::new_spanned(
+                    duplicate,
+                    "a method can take only one `&RequestContext`",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
 #[proc_macro_attribute]
 pub fn rest(_attr: TokenStream, item: TokenStream) -> TokenStream {
     item
 }
+
+#[proc_macro_attribute]
+pub fn jsonrpc(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+#[proc_macro_attribute]
+pub fn blocking(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+#[proc_macro_derive(RpcError, attributes(rpc))]
+pub fn derive_rpc_error(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    match rpc_error::derive(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
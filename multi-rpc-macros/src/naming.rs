@@ -0,0 +1,106 @@
+use proc_macro2::Span;
+use quote::format_ident;
+use syn::Ident;
+use syn::Path;
+use syn::Type;
+
+use crate::protocols::Protocol;
+
+/// Converts a `PascalCase` identifier such as `HttpGreeter` into `snake_case`.
+fn to_snake_case(ident: &Ident) -> String {
+    let chars: Vec<char> = ident.to_string().chars().collect();
+    let mut snake_case_name = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() {
+            let prev = i.checked_sub(1).map(|p| chars[p]);
+            let next = chars.get(i + 1);
+            let starts_word = prev.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit())
+                || (prev.is_some_and(|p| p.is_ascii_uppercase())
+                    && next.is_some_and(|n| n.is_ascii_lowercase()));
+            if starts_word {
+                snake_case_name.push('_');
+            }
+            snake_case_name.push(c.to_ascii_lowercase());
+        } else {
+            snake_case_name.push(c);
+        }
+    }
+    snake_case_name
+}
+
+/// Converts a `snake_case` name such as `update_settings` into `PascalCase`.
+pub fn to_pascal_case(name: &str) -> String {
+    let mut pascal_case_name = String::new();
+    let mut capitalize = true;
+    for c in name.chars() {
+        if c == '_' {
+            capitalize = true;
+        } else if capitalize {
+            pascal_case_name.push(c.to_ascii_uppercase());
+            capitalize = false;
+        } else {
+            pascal_case_name.push(c);
+        }
+    }
+    pascal_case_name
+}
+
+/// The module holding the code generated by `#[multi_rpc_trait]`, e.g. `greeter_protocols`.
+pub fn trait_module_ident(trait_ident: &Ident) -> Ident {
+    format_ident!("{}_protocols", to_snake_case(trait_ident))
+}
+
+/// The module holding the code generated by `#[multi_rpc_impl]`, named after the trait and the
+/// implementing type so that a trait can have several implementations, e.g.
+/// `greeter_for_my_greeter_impls` for `impl Greeter for MyGreeter`.
+pub fn impl_module_ident(trait_ident: &Ident, self_ty: &Type) -> syn::Result<Ident> {
+    Ok(format_ident!(
+        "{}_for_{}_impls",
+        to_snake_case(trait_ident),
+        to_snake_case(self_ty_ident(self_ty)?)
+    ))
+}
+
+// The name of the implementing type, e.g. `MyGreeter` for `MyGreeter<DB>` or `&'a MyGreeter`.
+fn self_ty_ident(self_ty: &Type) -> syn::Result<&Ident> {
+    match self_ty {
+        Type::Path(type_path) => Ok(&type_path.path.segments.last().unwrap().ident),
+        Type::Reference(reference) => self_ty_ident(&reference.elem),
+        Type::Group(group) => self_ty_ident(&group.elem),
+        Type::Paren(paren) => self_ty_ident(&paren.elem),
+        _ => Err(syn::Error::new_spanned(
+            self_ty,
+            "#[multi_rpc_impl] requires a named service type such as `MyService`",
+        )),
+    }
+}
+
+/// Resolves the trait module of `trait_path` as seen from inside the impl module.
+///
+/// `Greeter` becomes `super::greeter_protocols` and `crate::api::Greeter` becomes
+/// `crate::api::greeter_protocols`, because the impl module is one level below the `impl` block.
+pub fn trait_module_path(trait_path: &Path) -> Path {
+    let mut path = trait_path.clone();
+    let last = path.segments.pop().unwrap().into_value();
+    path.segments.push(trait_module_ident(&last.ident).into());
+
+    let is_absolute =
+        path.leading_colon.is_some() || path.segments.first().is_some_and(|s| s.ident == "crate");
+    if is_absolute {
+        return path;
+    }
+    if path.segments.first().is_some_and(|s| s.ident == "self") {
+        path.segments[0] = format_ident!("super").into();
+    } else {
+        path.segments.insert(0, format_ident!("super").into());
+    }
+    path
+}
+
+/// The hidden module the trait module declares for each protocol it generates, e.g. `__tarpc`.
+///
+/// The impl module imports it for each of its protocols, so that a protocol the trait doesn't
+/// generate fails to resolve at `span`.
+pub fn protocol_marker_ident(protocol: &dyn Protocol, span: Span) -> Ident {
+    Ident::new(&format!("__{}", protocol.name()), span)
+}
@@ -1,61 +1,392 @@
+use std::collections::HashSet;
+
 use proc_macro2::TokenStream;
 use quote::format_ident;
 use quote::quote;
+use syn::parse::Parse;
+use syn::parse::ParseStream;
+use syn::punctuated::Punctuated;
 use syn::FnArg;
 use syn::ImplItem;
+use syn::ImplItemFn;
 use syn::ItemImpl;
 use syn::ItemTrait;
-use syn::Pat;
+use syn::LitStr;
 use syn::ReturnType;
+use syn::Signature;
+use syn::Token;
 use syn::TraitItem;
+use syn::TraitItemFn;
 use syn::Type;
 
+use super::call_args;
+#[cfg(feature = "openrpc")]
+use super::doc_string;
+use super::is_bytes;
+#[cfg(feature = "openrpc")]
+use super::is_option;
+use super::multipart_args;
+use super::option_inner_ty;
+#[cfg(feature = "openrpc")]
+use super::result_err_ty;
+use super::result_ok_ty;
+use super::streaming_item;
+use super::takes_context;
+use super::wire_inputs;
 use super::Protocol;
+use crate::dispatch::Dispatch;
+
 pub struct JsonRpSee;
 
-impl Protocol for JsonRpSee {
-    fn transform_trait(&self, item_trait: &ItemTrait) -> TokenStream {
-        let rpc_trait_ident = format_ident!("{}Rpc", item_trait.ident);
-        let methods = item_trait.items.iter().filter_map(|item| {
-            if let TraitItem::Fn(method) = item {
-                let method_name = method.sig.ident.to_string();
+// The trait-level `#[jsonrpc(...)]` attribute.
+//
+// `namespace = "greeter"` prefixes every method name, e.g. `greeter_greet`.
+//
+// `client_params = named` makes the generated client send params as an object keyed by the
+// argument names instead of an array. It only concerns the client: the server accepts params by
+// position and by name either way.
+#[derive(Default)]
+struct JsonRpcTraitAttribute {
+    namespace: Option<LitStr>,
+    #[cfg_attr(not(feature = "jsonrpsee-client"), allow(dead_code))]
+    named_params: bool,
+}
 
-                // For jsonrpsee, always use &self in the generated trait,
-                // to align with the behavior of the `#[rpc]` macro.
-                let mut sig = method.sig.clone();
-                let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> =
-                    sig.inputs.iter().skip(1).cloned().collect();
+impl Parse for JsonRpcTraitAttribute {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut namespace = None;
+        let mut named_params = None;
 
-                sig.inputs = inputs;
-                sig.inputs.insert(0, syn::parse_quote! { &self });
+        let top_level_vars = Punctuated::<syn::Meta, Token![,]>::parse_terminated(input)?;
 
-                sig.output = syn::parse_quote! {
-                    -> Result<serde_json::Value, jsonrpsee::types::error::ErrorObject<'static>>
+        for meta in top_level_vars {
+            let key = meta.path();
+            if key.is_ident("namespace") {
+                let nv = meta.require_name_value()?;
+                set_once(&mut namespace, name_lit(&nv.value)?, key)?;
+            } else if key.is_ident("client_params") {
+                let nv = meta.require_name_value()?;
+                let named = match &nv.value {
+                    syn::Expr::Path(expr_path) if expr_path.path.is_ident("named") => true,
+                    syn::Expr::Path(expr_path) if expr_path.path.is_ident("positional") => false,
+                    value => {
+                        return Err(syn::Error::new_spanned(
+                            value,
+                            "expected `named` or `positional`",
+                        ))
+                    }
                 };
+                set_once(&mut named_params, named, key)?;
+            } else {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    "unknown `#[jsonrpc]` argument on a trait, expected `namespace` or `client_params`",
+                ));
+            }
+        }
 
-                Some(quote! {
-                    #[method(name = #method_name)]
-                    #sig;
-                })
+        Ok(JsonRpcTraitAttribute {
+            namespace,
+            named_params: named_params.unwrap_or_default(),
+        })
+    }
+}
+
+// The method-level `#[jsonrpc(...)]` attribute.
+//
+// `name = "getGreeting"` replaces the Rust method name, and `aliases = ["greet"]` serves the
+// method under further names, which are not namespaced.
+#[derive(Default)]
+struct JsonRpcMethodAttribute {
+    name: Option<LitStr>,
+    aliases: Vec<LitStr>,
+}
+
+impl Parse for JsonRpcMethodAttribute {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut name = None;
+        let mut aliases = None;
+
+        let top_level_vars = Punctuated::<syn::Meta, Token![,]>::parse_terminated(input)?;
+
+        for meta in top_level_vars {
+            let key = meta.path();
+            if key.is_ident("name") {
+                let nv = meta.require_name_value()?;
+                set_once(&mut name, name_lit(&nv.value)?, key)?;
+            } else if key.is_ident("aliases") {
+                let nv = meta.require_name_value()?;
+                let syn::Expr::Array(array) = &nv.value else {
+                    return Err(syn::Error::new_spanned(
+                        &nv.value,
+                        "expected a list of names such as `[\"greet\"]`",
+                    ));
+                };
+                let list = array
+                    .elems
+                    .iter()
+                    .map(name_lit)
+                    .collect::<syn::Result<_>>()?;
+                set_once(&mut aliases, list, key)?;
             } else {
-                None
+                return Err(syn::Error::new_spanned(
+                    key,
+                    "unknown `#[jsonrpc]` argument on a method, expected `name` or `aliases`",
+                ));
+            }
+        }
+
+        Ok(JsonRpcMethodAttribute {
+            name,
+            aliases: aliases.unwrap_or_default(),
+        })
+    }
+}
+
+// Stores the value of a `#[jsonrpc]` argument, rejecting arguments given more than once.
+fn set_once<T>(slot: &mut Option<T>, value: T, key: &syn::Path) -> syn::Result<()> {
+    if slot.is_some() {
+        return Err(syn::Error::new_spanned(
+            key,
+            "argument may only be specified once",
+        ));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+// A non-empty string literal naming a method or namespace.
+fn name_lit(expr: &syn::Expr) -> syn::Result<LitStr> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(lit_str),
+            ..
+        }) if !lit_str.value().is_empty() => Ok(lit_str.clone()),
+        _ => Err(syn::Error::new_spanned(
+            expr,
+            "expected a non-empty string literal",
+        )),
+    }
+}
+
+fn jsonrpc_attr<T: Parse + Default>(attrs: &[syn::Attribute]) -> syn::Result<T> {
+    match attrs.iter().find(|attr| attr.path().is_ident("jsonrpc")) {
+        Some(attr) => attr.parse_args(),
+        None => Ok(T::default()),
+    }
+}
+
+// The JSON-RPC interface of a trait: its methods under their wire names.
+struct JsonRpcInterface<'a> {
+    #[cfg_attr(not(feature = "jsonrpsee-client"), allow(dead_code))]
+    named_params: bool,
+    methods: Vec<JsonRpcMethod<'a>>,
+}
+
+struct JsonRpcMethod<'a> {
+    method: &'a TraitItemFn,
+    name: String,
+    aliases: Vec<String>,
+    // The method cancelling the subscription of a streaming method.
+    unsubscribe_name: String,
+    unsubscribe_aliases: Vec<String>,
+}
+
+impl<'a> JsonRpcInterface<'a> {
+    // Resolves the wire names of the methods, rejecting names used more than once, as jsonrpsee
+    // would only detect them when the server starts.
+    fn from_trait(item_trait: &'a ItemTrait) -> syn::Result<Self> {
+        let trait_attr: JsonRpcTraitAttribute = jsonrpc_attr(&item_trait.attrs)?;
+        let namespaced = |name: String| match &trait_attr.namespace {
+            Some(namespace) => format!("{}_{}", namespace.value(), name),
+            None => name,
+        };
+
+        let mut seen = HashSet::new();
+        // Served by the module itself with the `openrpc` feature.
+        seen.insert("rpc.discover".to_string());
+
+        let mut methods = Vec::new();
+        for item in &item_trait.items {
+            let TraitItem::Fn(method) = item else {
+                continue;
+            };
+            let method_attr: JsonRpcMethodAttribute = jsonrpc_attr(&method.attrs)?;
+            let base_name = match &method_attr.name {
+                Some(name) => name.value(),
+                None => method.sig.ident.to_string(),
+            };
+            let aliases: Vec<String> = method_attr.aliases.iter().map(LitStr::value).collect();
+            let method = JsonRpcMethod {
+                method,
+                name: namespaced(base_name.clone()),
+                unsubscribe_name: namespaced(format!("unsubscribe_{}", base_name)),
+                unsubscribe_aliases: aliases
+                    .iter()
+                    .map(|alias| format!("unsubscribe_{}", alias))
+                    .collect(),
+                aliases,
+            };
+
+            // Each name with the span of its declaration, where a collision is reported.
+            let name_span = match &method_attr.name {
+                Some(lit) => lit.span(),
+                None => method.method.sig.ident.span(),
+            };
+            let alias_spans = method_attr.aliases.iter().map(LitStr::span);
+            let mut names = vec![(&method.name, name_span)];
+            names.extend(method.aliases.iter().zip(alias_spans.clone()));
+            if streaming_item(&method.method.sig).is_some() {
+                names.push((&method.unsubscribe_name, name_span));
+                names.extend(method.unsubscribe_aliases.iter().zip(alias_spans));
+            }
+            for (name, span) in names {
+                if !seen.insert(name.clone()) {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("the JSON-RPC method name `{}` is used more than once", name),
+                    ));
+                }
+            }
+            methods.push(method);
+        }
+
+        Ok(JsonRpcInterface {
+            named_params: trait_attr.named_params,
+            methods,
+        })
+    }
+}
+
+impl Protocol for JsonRpSee {
+    fn name(&self) -> &'static str {
+        "jsonrpsee"
+    }
+
+    fn transform_trait(&self, item_trait: &ItemTrait) -> syn::Result<TokenStream> {
+        let rpc_trait_ident = format_ident!("{}Rpc", item_trait.ident);
+        let interface = JsonRpcInterface::from_trait(item_trait)?;
+        let client = rpc_client(item_trait, &interface);
+        let openrpc = openrpc(item_trait, &interface)?;
+        let methods = interface.methods.iter().map(|rpc_method| {
+            let JsonRpcMethod {
+                method,
+                name,
+                aliases,
+                unsubscribe_name,
+                unsubscribe_aliases,
+            } = rpc_method;
+
+            // For jsonrpsee, always use &self in the generated trait,
+            // to align with the behavior of the `#[rpc]` macro.
+            let mut sig = method.sig.clone();
+            sig.inputs = rpc_params(&method.sig);
+            sig.inputs.insert(0, syn::parse_quote! { &self });
+            // The adapter must lock the service, so sync methods are served asynchronously.
+            sig.asyncness = Some(Default::default());
+            // The `RequestContext` is read from the extensions of the request.
+            let with_extensions = takes_context(&method.sig).then(|| quote! { , with_extensions });
+
+            // Streaming methods become subscriptions, which push every item to the client.
+            if let Some(item_ty) = streaming_item(&method.sig) {
+                sig.output = syn::parse_quote! { -> jsonrpsee::core::SubscriptionResult };
+                return quote! {
+                    #[subscription(
+                        name = #name,
+                        aliases = [#(#aliases),*],
+                        unsubscribe = #unsubscribe_name,
+                        unsubscribe_aliases = [#(#unsubscribe_aliases),*],
+                        item = #item_ty
+                        #with_extensions
+                    )]
+                    #sig;
+                };
+            }
+
+            sig.output = syn::parse_quote! {
+                -> Result<serde_json::Value, jsonrpsee::types::error::ErrorObject<'static>>
+            };
+
+            quote! {
+                #[method(name = #name, aliases = [#(#aliases),*] #with_extensions)]
+                #sig;
             }
         });
-        quote! {
+        Ok(quote! {
             use jsonrpsee::proc_macros::rpc;
             #[rpc(server)]
             pub trait #rpc_trait_ident { #(#methods)* }
 
-            #[derive(Clone)]
-            pub struct RpcAdapter<S>(
-                // An Arc reference to the Mutex in ServerBuilder
-                pub std::sync::Arc<tokio::sync::Mutex<S>>
+            #client
+
+            #openrpc
+
+            pub struct RpcAdapter<H>(
+                // The service handle from ServerBuilder: an `Arc<RwLock<S>>` shared with the
+                // other servers, or an `ActorHandle` in actor mode.
+                pub H
             );
+
+            // Implemented by hand so that cloning the adapter only clones the handle, never the service.
+            impl<H: Clone> Clone for RpcAdapter<H> {
+                fn clone(&self) -> Self {
+                    Self(self.0.clone())
+                }
+            }
+        })
+    }
+
+    fn transform_remote(&self, item_trait: &ItemTrait) -> syn::Result<TokenStream> {
+        if !cfg!(feature = "jsonrpsee-client") {
+            return Ok(quote! {});
         }
+        let interface = JsonRpcInterface::from_trait(item_trait)?;
+        let arms = interface.methods.iter().map(|rpc_method| {
+            let method_name = rpc_method.method.sig.ident.to_string();
+            let name = &rpc_method.name;
+            quote! { #method_name => #name, }
+        });
+        let doc = format!(
+            "Maps the methods of `{}` to their JSON-RPC names, for \
+             `multi_rpc::client::jsonrpc::JsonRpcTransport::with_method_names`.",
+            item_trait.ident
+        );
+        let remote_ident = format_ident!("{}Remote", item_trait.ident);
+
+        Ok(quote! {
+            #[doc = #doc]
+            pub fn jsonrpc_method_name(method: &str) -> &str {
+                match method {
+                    #(#arms)*
+                    method => method,
+                }
+            }
+
+            impl #remote_ident {
+                /// Creates a remote service that calls a JSON-RPC server through `client`, e.g. a
+                /// jsonrpsee `HttpClient` or `WsClient`, under the JSON-RPC names of the methods.
+                pub fn jsonrpc<C>(client: C) -> Self
+                where
+                    multi_rpc::client::jsonrpc::JsonRpcTransport<C>: multi_rpc::client::Transport + 'static,
+                {
+                    Self::new(
+                        multi_rpc::client::jsonrpc::JsonRpcTransport::new(client)
+                            .with_method_names(jsonrpc_method_name),
+                    )
+                }
+            }
+        })
     }
 
-    fn transform_impl(&self, item_impl: &ItemImpl) -> TokenStream {
-        let self_ty = &item_impl.self_ty;
+    fn transform_impl(
+        &self,
+        item_impl: &ItemImpl,
+        dispatch: &Dispatch,
+    ) -> syn::Result<TokenStream> {
+        let handle_ty = dispatch.handle_ty(item_impl);
+        let generics = dispatch.generics(item_impl);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let turbofish = ty_generics.as_turbofish();
         let trait_ident = &item_impl
             .trait_
             .as_ref()
@@ -69,38 +400,90 @@ impl Protocol for JsonRpSee {
         let method_impls = item_impl.items.iter().filter_map(|item| {
             if let ImplItem::Fn(method) = item {
                 let sig = &method.sig;
-                let method_ident = &sig.ident;
-                let arg_names: Vec<Pat> = method
-                    .sig
-                    .inputs
-                    .iter()
-                    .skip(1)
-                    .filter_map(|arg| {
-                        if let FnArg::Typed(pt) = arg {
-                            Some((*pt.pat).clone())
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
+                let arg_names = call_args(sig, &quote! { request_context });
+                // Methods taking a `RequestContext` receive the request extensions after the
+                // receiver and the subscription sink.
+                let (extensions_arg, context) = if takes_context(sig) {
+                    (
+                        Some(quote! { ext: &jsonrpsee::Extensions }),
+                        quote! {
+                            let request_context = multi_rpc::context::RequestContext::from_jsonrpsee(ext);
+                        },
+                    )
+                } else {
+                    (None, quote! {})
+                };
 
-                let (adapted_sig, body) = {
-                    let mut is_result = false;
+                if streaming_item(sig).is_some() {
                     let mut adapted_sig = sig.clone();
+                    adapted_sig.inputs = rpc_params(sig);
+                    if let Some(extensions_arg) = &extensions_arg {
+                        adapted_sig.inputs.insert(0, syn::parse_quote! { #extensions_arg });
+                    }
+                    adapted_sig.inputs.insert(0, syn::parse_quote! { pending: jsonrpsee::PendingSubscriptionSink });
+                    adapted_sig.inputs.insert(0, syn::parse_quote! { &self });
+                    adapted_sig.asyncness = Some(Default::default());
+                    adapted_sig.output = syn::parse_quote! { -> jsonrpsee::core::SubscriptionResult };
 
-                    let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> =
-                        adapted_sig.inputs.iter().skip(1).cloned().collect();
+                    let on_actor_error = quote! {{
+                        pending
+                            .reject(jsonrpsee::types::error::ErrorObject::owned(
+                                multi_rpc::error::ProtocolError::jsonrpc_code(&err),
+                                err.to_string(),
+                                None::<()>,
+                            ))
+                            .await;
+                        return Ok(());
+                    }};
+                    let method_call = dispatch.call(&quote! { self.0 }, method, &arg_names, &on_actor_error);
+                    let decode_binary = decode_binary_params(method, &quote! {{
+                        pending.reject(err).await;
+                        return Ok(());
+                    }});
+                    return Some(quote! {
+                        #adapted_sig {
+                            use futures::StreamExt;
 
-                    adapted_sig.inputs = inputs;
+                            #context
+                            #(#decode_binary)*
+                            let mut stream = #method_call;
+                            let sink = pending.accept().await?;
+                            while let Some(item) = stream.next().await {
+                                let message = jsonrpsee::core::to_json_raw_value(&item)?;
+                                if sink.send(message).await.is_err() {
+                                    // The client unsubscribed or disconnected.
+                                    break;
+                                }
+                            }
+                            Ok(())
+                        }
+                    });
+                }
+
+                let (adapted_sig, body, decode_binary) = {
+                    let mut is_result = false;
+                    let mut adapted_sig = sig.clone();
+                    adapted_sig.inputs = rpc_params(sig);
+                    if let Some(extensions_arg) = &extensions_arg {
+                        adapted_sig.inputs.insert(0, syn::parse_quote! { #extensions_arg });
+                    }
 
                     // Always add an immutable &self receiver for the jsonrpsee adapter methods
                     adapted_sig.inputs.insert(0, syn::parse_quote! { &self });
+                    adapted_sig.asyncness = Some(Default::default());
 
                     adapted_sig.output = syn::parse_quote! {
                         -> Result<serde_json::Value, jsonrpsee::types::error::ErrorObject<'static>>
                     };
 
-                    let method_call = quote! { self.0.lock().await.#method_ident(#(#arg_names),*).await };
+                    let on_actor_error = quote! {
+                        return Err(jsonrpsee::types::error::ErrorObject::owned(
+                            multi_rpc::error::ProtocolError::jsonrpc_code(&err),
+                            err.to_string(),
+                            None::<()>,
+                        ))
+                    };
+                    let method_call = dispatch.call(&quote! { self.0 }, method, &arg_names, &on_actor_error);
 
                     if let ReturnType::Type(_, ty) = &sig.output {
                         if let Type::Path(type_path) = &**ty {
@@ -125,11 +508,15 @@ impl Protocol for JsonRpSee {
                                         )),
                                     }
                                 }
-                                Err(err) => Err(jsonrpsee::types::error::ErrorObject::owned(
-                                    jsonrpsee::types::error::ErrorCode::InternalError.code(),
-                                    err.to_string(),
-                                    None::<()>,
-                                )),
+                                Err(err) => {
+                                    use multi_rpc::error::MapOtherError as _;
+                                    use multi_rpc::error::MapProtocolError as _;
+                                    Err(jsonrpsee::types::error::ErrorObject::owned(
+                                        (&multi_rpc::error::ErrorMapping(&err)).jsonrpc_code(),
+                                        err.to_string(),
+                                        (&multi_rpc::error::ErrorMapping(&err)).data(),
+                                    ))
+                                }
                             }
                         }
                     } else {
@@ -144,10 +531,13 @@ impl Protocol for JsonRpSee {
                             }
                         }
                     };
-                    (adapted_sig, body_logic)
+                    let decode_binary = decode_binary_params(method, &quote! { return Err(err) });
+                    (adapted_sig, body_logic, decode_binary)
                 };
                 Some(quote! {
                     #adapted_sig {
+                        #context
+                        #(#decode_binary)*
                         #body
                     }
                 })
@@ -156,25 +546,301 @@ impl Protocol for JsonRpSee {
             }
         });
 
-        quote! {
+        // With `openrpc`, the module also answers `rpc.discover` with the OpenRPC document.
+        let (module_binding, discover) = if cfg!(feature = "openrpc") {
+            (
+                quote! { mut module },
+                quote! {
+                    let document = openrpc();
+                    module
+                        .register_method(multi_rpc::openrpc::DISCOVER_METHOD, move |_, _, _| {
+                            document.clone()
+                        })
+                        .expect("no service method is named `rpc.discover`");
+                },
+            )
+        } else {
+            (quote! { module }, quote! {})
+        };
+
+        Ok(quote! {
             #[jsonrpsee::core::async_trait]
-            impl #rpc_trait_ident for RpcAdapter<#self_ty>
+            impl #impl_generics #rpc_trait_ident for RpcAdapter<#handle_ty>
+            #where_clause
             {
                 #(#method_impls)*
             }
 
-            pub fn jsonrpsee(addr: std::net::SocketAddr)
-                -> impl FnOnce(std::sync::Arc<tokio::sync::Mutex<#self_ty>>) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>
+            pub fn jsonrpsee #impl_generics (addr: std::net::SocketAddr)
+                -> impl FnOnce(#handle_ty) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>
+            #where_clause
+            {
+                jsonrpsee_with_config #turbofish (addr, multi_rpc::config::JsonRpcConfig::default())
+            }
+
+            pub fn jsonrpsee_with_config #impl_generics (
+                addr: std::net::SocketAddr,
+                config: multi_rpc::config::JsonRpcConfig,
+            ) -> impl FnOnce(#handle_ty) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>
+            #where_clause
             {
                 move |service| {
                     Box::pin(async move {
-                        let module = RpcAdapter(service).into_rpc();
-                        println!("🌐 JSON-RPC (jsonrpsee) server listening on http://{}", addr);
-                        let server = jsonrpsee::server::Server::builder().build(addr).await.unwrap();
-                        server.start(module).stopped().await;
+                        let #module_binding = RpcAdapter(service).into_rpc();
+                        #discover
+                        config.serve(addr, module).await.unwrap();
                     })
                 }
             }
+        })
+    }
+}
+
+// Generates the typed `{Trait}RpcClient`, implemented for jsonrpsee's HTTP and WebSocket clients.
+//
+// The server trait responds with `serde_json::Value`, as jsonrpsee requires typed responses to be
+// `Clone`. The client is declared by a separate client-only trait of the same name, whose methods
+// decode the responses into the original return types instead.
+#[cfg(feature = "jsonrpsee-client")]
+fn rpc_client(item_trait: &ItemTrait, interface: &JsonRpcInterface) -> TokenStream {
+    let param_kind = if interface.named_params {
+        quote! { param_kind = map }
+    } else {
+        quote! { param_kind = array }
+    };
+    let rpc_trait_ident = format_ident!("{}Rpc", item_trait.ident);
+    let client_ident = format_ident!("{}RpcClient", item_trait.ident);
+    let methods = interface.methods.iter().map(|rpc_method| {
+        let JsonRpcMethod {
+            method,
+            name,
+            unsubscribe_name,
+            ..
+        } = rpc_method;
+        let mut sig = method.sig.clone();
+        sig.inputs = wire_inputs(&method.sig).cloned().collect();
+        sig.inputs.insert(0, syn::parse_quote! { &self });
+        sig.asyncness = Some(Default::default());
+
+        if let Some(item_ty) = streaming_item(&method.sig) {
+            sig.output = syn::parse_quote! { -> jsonrpsee::core::SubscriptionResult };
+            return quote! {
+                #[subscription(name = #name, unsubscribe = #unsubscribe_name, item = #item_ty, #param_kind)]
+                #sig;
+            };
         }
+
+        let return_ty = rpc_return_ty(&method.sig);
+        sig.output = syn::parse_quote! {
+            -> Result<#return_ty, jsonrpsee::types::error::ErrorObjectOwned>
+        };
+        quote! {
+            #[method(name = #name, #param_kind)]
+            #sig;
+        }
+    });
+
+    quote! {
+        pub use self::rpc_client::#client_ident;
+
+        mod rpc_client {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #[rpc(client)]
+            pub trait #rpc_trait_ident { #(#methods)* }
+        }
+    }
+}
+
+#[cfg(not(feature = "jsonrpsee-client"))]
+fn rpc_client(_: &ItemTrait, _: &JsonRpcInterface) -> TokenStream {
+    quote! {}
+}
+
+// Generates `openrpc()`, the OpenRPC document describing the JSON-RPC methods of the trait.
+//
+// Params are described in the order of the method's arguments, and may be sent by position or by
+// name. A method returning `Result<T, E>` lists the error codes of `E`, see `ErrorsMapping`.
+#[cfg(feature = "openrpc")]
+fn openrpc(item_trait: &ItemTrait, interface: &JsonRpcInterface) -> syn::Result<TokenStream> {
+    let trait_ident = &item_trait.ident;
+    let title = trait_ident.to_string();
+
+    let mut methods = Vec::new();
+    for rpc_method in &interface.methods {
+        let method = rpc_method.method;
+        let method_name = &rpc_method.name;
+
+        // Schemas are computed up front, as types with commas can't be written inside `json!`.
+        let mut schemas = Vec::new();
+        let mut schema_for = |ty: &Type| {
+            let ident = format_ident!("schema_{}", schemas.len());
+            schemas.push(quote! {
+                let #ident = (&multi_rpc::schema::SchemaMapping::<#ty>::new()).schema(spec.generator());
+            });
+            ident
+        };
+
+        let mut params = Vec::new();
+        for arg in wire_inputs(&method.sig) {
+            let FnArg::Typed(pt) = arg else {
+                continue;
+            };
+            let syn::Pat::Ident(pi) = &*pt.pat else {
+                return Err(syn::Error::new_spanned(
+                    &pt.pat,
+                    "arguments must be plain identifiers to be described in OpenRPC",
+                ));
+            };
+            let name = pi.ident.to_string();
+            let required = !is_option(&pt.ty);
+            let schema = schema_for(&pt.ty);
+            params.push(quote! {
+                { "name": #name, "required": #required, "schema": #schema }
+            });
+        }
+
+        let mut extra = Vec::new();
+        let result_schema = if let Some(item_ty) = streaming_item(&method.sig) {
+            // OpenRPC has no subscriptions, the result describes the items sent to the subscriber.
+            let unsubscribe_name = &rpc_method.unsubscribe_name;
+            extra.push(quote! {
+                method["x-subscription"] = serde_json::json!({ "unsubscribe": #unsubscribe_name });
+            });
+            schema_for(item_ty)
+        } else {
+            let err_ty = match &method.sig.output {
+                ReturnType::Type(_, ty) => result_err_ty(ty),
+                ReturnType::Default => None,
+            };
+            if let Some(err_ty) = err_ty {
+                extra.push(quote! {
+                    let errors = (&multi_rpc::openrpc::ErrorsMapping::<#err_ty>::new()).errors();
+                    if !errors.is_empty() {
+                        method["errors"] = serde_json::Value::Array(errors);
+                    }
+                });
+            }
+            let return_ty = rpc_return_ty(&method.sig);
+            schema_for(&syn::parse2(return_ty)?)
+        };
+        if let Some(description) = doc_string(&method.attrs) {
+            extra.push(quote! {
+                method["description"] = serde_json::Value::from(#description);
+            });
+        }
+
+        methods.push(quote! {
+            {
+                use multi_rpc::schema::MapJsonSchema as _;
+                use multi_rpc::schema::MapOtherSchema as _;
+                use multi_rpc::openrpc::MapProtocolErrors as _;
+                use multi_rpc::openrpc::MapOtherErrors as _;
+                #(#schemas)*
+                #[allow(unused_mut)]
+                let mut method = serde_json::json!({
+                    "name": #method_name,
+                    "paramStructure": "either",
+                    "params": [#(#params),*],
+                    "result": { "name": "result", "schema": #result_schema },
+                });
+                #(#extra)*
+                spec.add_method(method);
+            }
+        });
+    }
+
+    let doc = format!(
+        "The OpenRPC document describing the JSON-RPC methods of `{}`.",
+        trait_ident
+    );
+    Ok(quote! {
+        #[doc = #doc]
+        pub fn openrpc() -> serde_json::Value {
+            let mut spec = multi_rpc::openrpc::OpenRpc::new();
+            #(#methods)*
+            spec.into_document(#title, env!("CARGO_PKG_VERSION"))
+        }
+    })
+}
+
+#[cfg(not(feature = "openrpc"))]
+fn openrpc(_: &ItemTrait, _: &JsonRpcInterface) -> syn::Result<TokenStream> {
+    Ok(quote! {})
+}
+
+// The params of the server methods: the wire inputs, with binary arguments received as a
+// `multi_rpc::binary::BinaryParam`, since only the impl knows which of them accept base64.
+fn rpc_params(sig: &Signature) -> Punctuated<FnArg, Token![,]> {
+    wire_inputs(sig)
+        .map(|arg| match arg {
+            FnArg::Typed(pt) => {
+                let mut pt = pt.clone();
+                pt.ty = Box::new(binary_param_ty(&pt.ty));
+                FnArg::Typed(pt)
+            }
+            FnArg::Receiver(_) => arg.clone(),
+        })
+        .collect()
+}
+
+// `multi_rpc::binary::BinaryParam<T>` for `Vec<u8>` and `Bytes`, also inside an `Option`, or else
+// `ty`.
+fn binary_param_ty(ty: &Type) -> Type {
+    match option_inner_ty(ty) {
+        Some(inner_ty) if is_bytes(inner_ty) => {
+            syn::parse_quote! { Option<multi_rpc::binary::BinaryParam<#inner_ty>> }
+        }
+        _ if is_bytes(ty) => syn::parse_quote! { multi_rpc::binary::BinaryParam<#ty> },
+        _ => ty.clone(),
+    }
+}
+
+// The statements decoding the `BinaryParam`s of `rpc_params` into the method's arguments, with
+// base64 accepted for the arguments bound to the method's `multipart(...)` group. A param that
+// can't be decoded runs `on_error` with the error object in `err`.
+fn decode_binary_params(method: &ImplItemFn, on_error: &TokenStream) -> Vec<TokenStream> {
+    let multipart = multipart_args(&method.attrs);
+    wire_inputs(&method.sig)
+        .filter_map(|arg| {
+            let FnArg::Typed(pt) = arg else {
+                return None;
+            };
+            let syn::Pat::Ident(pi) = &*pt.pat else {
+                return None;
+            };
+            let ident = &pi.ident;
+            let base64 = multipart.contains(ident);
+            let decoded = match option_inner_ty(&pt.ty) {
+                Some(inner_ty) if is_bytes(inner_ty) => quote! {
+                    #ident.map(|param| param.decode(#base64)).transpose()
+                },
+                _ if is_bytes(&pt.ty) => quote! { #ident.decode(#base64) },
+                _ => return None,
+            };
+            Some(quote! {
+                let #ident = match #decoded {
+                    Ok(#ident) => #ident,
+                    Err(err) => #on_error,
+                };
+            })
+        })
+        .collect()
+}
+
+// The type a JSON-RPC method responds with: the `T` of a `Result<T, E>`, whose error is sent as
+// a JSON-RPC error object, or else the method's return type.
+#[cfg_attr(
+    not(any(feature = "jsonrpsee-client", feature = "openrpc")),
+    allow(dead_code)
+)]
+fn rpc_return_ty(sig: &Signature) -> TokenStream {
+    match &sig.output {
+        ReturnType::Default => quote! { () },
+        ReturnType::Type(_, ty) => match result_ok_ty(ty) {
+            Some(ok_ty) => quote! { #ok_ty },
+            None => quote! { #ty },
+        },
     }
 }
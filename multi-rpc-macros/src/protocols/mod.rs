@@ -1,14 +1,22 @@
 use proc_macro2::TokenStream;
+use quote::quote;
+use syn::FnArg;
 use syn::ItemImpl;
 use syn::ItemTrait;
+use syn::PathArguments;
+use syn::ReturnType;
+use syn::Signature;
+use syn::Type;
+
+use crate::dispatch::Dispatch;
 
 #[cfg(feature = "tarpc")]
 mod tarpc;
 #[cfg(feature = "tarpc")]
 pub use tarpc::Tarpc;
-#[cfg(feature = "rest-axum")]
+#[cfg(feature = "rest")]
 mod rest_axum;
-#[cfg(feature = "rest-axum")]
+#[cfg(feature = "rest")]
 pub use rest_axum::RestAxum;
 #[cfg(feature = "jsonrpsee")]
 mod jsonrpsee;
@@ -17,44 +25,278 @@ pub use jsonrpsee::JsonRpSee;
 
 #[cfg(not(feature = "tarpc"))]
 pub struct Tarpc;
-#[cfg(not(feature = "rest-axum"))]
+#[cfg(not(feature = "rest"))]
 pub struct RestAxum;
 #[cfg(not(feature = "jsonrpsee"))]
 pub struct JsonRpSee;
 
 /// A trait defining a consistent interface for all RPC protocol generators.
 pub trait Protocol: Sync {
+    /// The name used to select this protocol in `protocols(...)`.
+    fn name(&self) -> &'static str;
+    /// The cargo feature of `multi-rpc` that enables this protocol.
+    fn feature(&self) -> String {
+        self.name().replace('_', "-")
+    }
+    /// Whether the protocol's feature is enabled for this build.
+    fn is_enabled(&self) -> bool {
+        true
+    }
     /// Transforms the user's trait definition.
-    fn transform_trait(&self, item_trait: &ItemTrait) -> TokenStream;
+    fn transform_trait(&self, item_trait: &ItemTrait) -> syn::Result<TokenStream>;
+    /// Generates the protocol's client support for `#[multi_rpc_trait(remote)]`, such as a
+    /// `multi_rpc::client::Transport` impl for its client.
+    fn transform_remote(&self, _item_trait: &ItemTrait) -> syn::Result<TokenStream> {
+        Ok(quote::quote! {})
+    }
     /// Transforms the user's `impl` block to generate adapter implementations.
-    fn transform_impl(&self, item_impl: &ItemImpl) -> TokenStream;
+    fn transform_impl(&self, item_impl: &ItemImpl, dispatch: &Dispatch)
+        -> syn::Result<TokenStream>;
+}
+
+/// The item type `T` of a server-streaming method, i.e. one returning `Streaming<T>`.
+pub fn streaming_item(sig: &Signature) -> Option<&Type> {
+    let ReturnType::Type(_, ty) = &sig.output else {
+        return None;
+    };
+    let Type::Path(type_path) = &**ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Streaming" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        syn::GenericArgument::Type(item_ty) => Some(item_ty),
+        _ => None,
+    }
+}
+
+/// Whether `arg` is a `&RequestContext` parameter, which the adapters fill in from the request
+/// instead of reading it from the wire params.
+pub fn is_context_arg(arg: &FnArg) -> bool {
+    let FnArg::Typed(pt) = arg else {
+        return false;
+    };
+    let Type::Reference(reference) = &*pt.ty else {
+        return false;
+    };
+    matches!(&*reference.elem, Type::Path(type_path) if type_path.path.segments.last().is_some_and(|s| s.ident == "RequestContext"))
+}
+
+/// Whether the method takes a `&RequestContext` parameter.
+#[cfg_attr(
+    not(any(feature = "tarpc", feature = "rest", feature = "jsonrpsee")),
+    allow(dead_code)
+)]
+pub fn takes_context(sig: &Signature) -> bool {
+    sig.inputs.iter().any(is_context_arg)
+}
+
+/// The arguments of the method that are sent over the wire, i.e. without the receiver and the
+/// `&RequestContext` parameter.
+#[cfg_attr(
+    not(any(feature = "tarpc", feature = "rest", feature = "jsonrpsee")),
+    allow(dead_code)
+)]
+pub fn wire_inputs(sig: &Signature) -> impl Iterator<Item = &FnArg> {
+    sig.inputs
+        .iter()
+        .filter(|arg| matches!(arg, FnArg::Typed(_)) && !is_context_arg(arg))
+}
+
+/// The arguments for calling the method from an adapter: the argument patterns, with `context`
+/// passed for the `&RequestContext` parameter.
+#[cfg_attr(not(any(feature = "tarpc", feature = "jsonrpsee")), allow(dead_code))]
+pub fn call_args(sig: &Signature, context: &TokenStream) -> Vec<TokenStream> {
+    sig.inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(_) if is_context_arg(arg) => Some(context.clone()),
+            FnArg::Typed(pt) => {
+                let pat = &pt.pat;
+                Some(quote! { #pat })
+            }
+            FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+/// The `T` of a `Result<T, E>` return type.
+pub fn result_ok_ty(ty: &Type) -> Option<&Type> {
+    result_arg(ty, 0)
+}
+
+/// The `E` of a `Result<T, E>` return type.
+#[cfg(feature = "openrpc")]
+pub fn result_err_ty(ty: &Type) -> Option<&Type> {
+    result_arg(ty, 1)
+}
+
+fn result_arg(ty: &Type, index: usize) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Result" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.iter().nth(index)? {
+        syn::GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
 }
 
 // --- Dummy Trait Impls for Disabled Features ---
 #[cfg(not(feature = "tarpc"))]
 impl Protocol for Tarpc {
-    fn transform_trait(&self, _: &ItemTrait) -> TokenStream {
-        quote::quote! {}
+    fn name(&self) -> &'static str {
+        "tarpc"
+    }
+    fn is_enabled(&self) -> bool {
+        false
     }
-    fn transform_impl(&self, _: &ItemImpl) -> TokenStream {
-        quote::quote! {}
+    fn transform_trait(&self, _: &ItemTrait) -> syn::Result<TokenStream> {
+        Ok(quote::quote! {})
+    }
+    fn transform_impl(&self, _: &ItemImpl, _: &Dispatch) -> syn::Result<TokenStream> {
+        Ok(quote::quote! {})
     }
 }
-#[cfg(not(feature = "rest-axum"))]
+#[cfg(not(feature = "rest"))]
 impl Protocol for RestAxum {
-    fn transform_trait(&self, _: &ItemTrait) -> TokenStream {
-        quote::quote! {}
+    fn name(&self) -> &'static str {
+        "rest_axum"
+    }
+    fn is_enabled(&self) -> bool {
+        false
+    }
+    fn transform_trait(&self, _: &ItemTrait) -> syn::Result<TokenStream> {
+        Ok(quote::quote! {})
     }
-    fn transform_impl(&self, _: &ItemImpl) -> TokenStream {
-        quote::quote! {}
+    fn transform_impl(&self, _: &ItemImpl, _: &Dispatch) -> syn::Result<TokenStream> {
+        Ok(quote::quote! {})
     }
 }
 #[cfg(not(feature = "jsonrpsee"))]
 impl Protocol for JsonRpSee {
-    fn transform_trait(&self, _: &ItemTrait) -> TokenStream {
-        quote::quote! {}
+    fn name(&self) -> &'static str {
+        "jsonrpsee"
+    }
+    fn is_enabled(&self) -> bool {
+        false
+    }
+    fn transform_trait(&self, _: &ItemTrait) -> syn::Result<TokenStream> {
+        Ok(quote::quote! {})
+    }
+    fn transform_impl(&self, _: &ItemImpl, _: &Dispatch) -> syn::Result<TokenStream> {
+        Ok(quote::quote! {})
+    }
+}
+
+/// The doc comments in `attrs` joined into one string, if there are any.
+#[cfg(any(feature = "openapi", feature = "openrpc"))]
+pub fn doc_string(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(nv) if nv.path.is_ident("doc") => match &nv.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(s),
+                    ..
+                }) => Some(s.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Whether `ty` is an `Option`, i.e. an optional parameter.
+#[cfg(any(feature = "rest", feature = "openrpc"))]
+pub fn is_option(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.segments.last().is_some_and(|s| s.ident == "Option"))
+}
+
+/// The `T` of an `Option<T>`.
+#[cfg(any(feature = "rest", feature = "jsonrpsee"))]
+pub fn option_inner_ty(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
     }
-    fn transform_impl(&self, _: &ItemImpl) -> TokenStream {
-        quote::quote! {}
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        syn::GenericArgument::Type(inner_ty) => Some(inner_ty),
+        _ => None,
     }
 }
+
+/// Whether `ty` is binary data: `Vec<u8>` or `bytes::Bytes`.
+#[cfg(any(feature = "rest", feature = "jsonrpsee"))]
+pub fn is_bytes(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    let Some(segment) = type_path.path.segments.last() else {
+        return false;
+    };
+    if segment.ident == "Bytes" {
+        return true;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return false;
+    };
+    segment.ident == "Vec"
+        && matches!(
+            args.args.first(),
+            Some(syn::GenericArgument::Type(Type::Path(elem))) if elem.path.is_ident("u8")
+        )
+}
+
+/// The arguments bound to the `multipart(...)` group of a `#[rest]` attribute in `attrs`. Errors
+/// in the attribute are left to the REST protocol to report.
+#[cfg(feature = "jsonrpsee")]
+pub fn multipart_args(attrs: &[syn::Attribute]) -> Vec<syn::Ident> {
+    let Some(attr) = attrs.iter().find(|attr| attr.path().is_ident("rest")) else {
+        return Vec::new();
+    };
+    let Ok(metas) = attr.parse_args_with(
+        syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+    ) else {
+        return Vec::new();
+    };
+    let Some(syn::Meta::List(list)) = metas.iter().find(|meta| meta.path().is_ident("multipart"))
+    else {
+        return Vec::new();
+    };
+    let Ok(params) = list.parse_args_with(
+        syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+    ) else {
+        return Vec::new();
+    };
+    // Either `file`, or `name = file` for a field named differently from its argument.
+    params
+        .iter()
+        .filter_map(|param| match param {
+            syn::Meta::Path(path) => path.get_ident().cloned(),
+            syn::Meta::NameValue(nv) => match &nv.value {
+                syn::Expr::Path(expr_path) => expr_path.path.get_ident().cloned(),
+                _ => None,
+            },
+            syn::Meta::List(_) => None,
+        })
+        .collect()
+}
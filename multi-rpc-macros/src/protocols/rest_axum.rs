@@ -10,6 +10,7 @@ use syn::punctuated::Punctuated;
 use syn::FnArg;
 use syn::Ident;
 use syn::ImplItem;
+use syn::ImplItemFn;
 use syn::ItemImpl;
 use syn::ItemTrait;
 use syn::LitStr;
@@ -19,7 +20,19 @@ use syn::ReturnType;
 use syn::Token;
 use syn::Type;
 
+#[cfg(feature = "openapi")]
+use super::doc_string;
+use super::is_bytes;
+use super::is_context_arg;
+use super::is_option;
+use super::option_inner_ty;
+use super::result_ok_ty;
+use super::streaming_item;
+use super::takes_context;
+use super::wire_inputs;
 use super::Protocol;
+use crate::dispatch::Dispatch;
+use crate::naming::to_pascal_case;
 
 // Represents a mapping from a public API name to a private Rust variable name.
 // Can be either a simple identifier `limit` (shorthand for `limit = limit`)
@@ -48,6 +61,53 @@ impl Parse for ParamMapping {
     }
 }
 
+// Binds an argument to a header or cookie. Either a simple identifier `session_id`, named after
+// the argument, or an explicit name `api_version = "x-api-version"`.
+struct NamedParam {
+    private_name: Ident,
+    name: Option<LitStr>,
+}
+
+impl Parse for NamedParam {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let private_name: Ident = input.parse()?;
+        let name = if input.peek(Token![=]) {
+            let _eq_token: Token![=] = input.parse()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(NamedParam { private_name, name })
+    }
+}
+
+impl NamedParam {
+    // The header name: the explicit name in lowercase, or else the argument name with its
+    // underscores replaced by dashes.
+    fn header_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.value().to_lowercase(),
+            None => self.private_name.to_string().replace('_', "-"),
+        }
+    }
+
+    // The cookie name: the explicit name, or else the argument name.
+    fn cookie_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.value(),
+            None => self.private_name.to_string(),
+        }
+    }
+
+    // An error pointing at the name.
+    fn error(&self, message: &str) -> syn::Error {
+        match &self.name {
+            Some(name) => syn::Error::new_spanned(name, message),
+            None => syn::Error::new_spanned(&self.private_name, message),
+        }
+    }
+}
+
 // Main struct to parse the entire `#[rest(...)]` attribute.
 struct RestAttribute {
     method: Ident,
@@ -55,45 +115,156 @@ struct RestAttribute {
     query_params: Punctuated<ParamMapping, Token![,]>,
     body_params: Punctuated<ParamMapping, Token![,]>,
     form_params: Punctuated<ParamMapping, Token![,]>,
+    multipart_params: Punctuated<ParamMapping, Token![,]>,
+    header_params: Punctuated<NamedParam, Token![,]>,
+    cookie_params: Punctuated<NamedParam, Token![,]>,
+    // The status of successful responses, `200` if not given.
+    status: Option<syn::LitInt>,
+    // The media type of successful responses, `application/json` if not given.
+    produces: Option<LitStr>,
 }
 
+// The HTTP methods with a matching routing function in `axum::routing`.
+const HTTP_METHODS: &[&str] = &[
+    "GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS", "TRACE", "CONNECT",
+];
+
 impl Parse for RestAttribute {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut method = None;
         let mut path = None;
-        let mut query_params = Punctuated::new();
-        let mut body_params = Punctuated::new();
-        let mut form_params = Punctuated::new();
+        let mut query_params = None;
+        let mut body_params = None;
+        let mut form_params = None;
+        let mut multipart_params = None;
+        let mut header_params = None;
+        let mut cookie_params = None;
+        let mut status = None;
+        let mut produces = None;
 
         let top_level_vars = Punctuated::<syn::Meta, Token![,]>::parse_terminated(input)?;
 
         for meta in top_level_vars {
-            if meta.path().is_ident("method") {
-                if let syn::Meta::NameValue(nv) = meta {
-                    if let syn::Expr::Path(expr_path) = nv.value {
-                        method = expr_path.path.get_ident().cloned();
-                    }
+            let key = meta.path();
+            if key.is_ident("method") {
+                let nv = meta.require_name_value()?;
+                let ident = match &nv.value {
+                    syn::Expr::Path(expr_path) => expr_path.path.get_ident().cloned(),
+                    _ => None,
                 }
-            } else if meta.path().is_ident("path") {
-                if let syn::Meta::NameValue(nv) = meta {
-                    if let syn::Expr::Lit(expr_lit) = nv.value {
-                        if let syn::Lit::Str(lit_str) = expr_lit.lit {
-                            path = Some(lit_str);
-                        }
-                    }
-                }
-            } else if meta.path().is_ident("query") {
-                if let syn::Meta::List(list) = meta {
-                    query_params = list.parse_args_with(Punctuated::parse_terminated)?;
+                .ok_or_else(|| {
+                    syn::Error::new_spanned(&nv.value, "expected an HTTP method such as `GET`")
+                })?;
+                if !HTTP_METHODS.contains(&ident.to_string().to_uppercase().as_str()) {
+                    return Err(syn::Error::new_spanned(
+                        &ident,
+                        format!(
+                            "unknown HTTP method `{}`, expected one of {}",
+                            ident,
+                            HTTP_METHODS.join(", ")
+                        ),
+                    ));
                 }
-            } else if meta.path().is_ident("body") {
-                if let syn::Meta::List(list) = meta {
-                    body_params = list.parse_args_with(Punctuated::parse_terminated)?;
+                set_once(&mut method, ident, key)?;
+            } else if key.is_ident("path") {
+                let nv = meta.require_name_value()?;
+                let lit_str = match &nv.value {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(lit_str),
+                        ..
+                    }) => lit_str.clone(),
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            &nv.value,
+                            "expected a string literal such as `\"/users/{id}\"`",
+                        ))
+                    }
+                };
+                set_once(&mut path, lit_str, key)?;
+            } else if key.is_ident("query") {
+                let list = meta.require_list()?;
+                set_once(
+                    &mut query_params,
+                    list.parse_args_with(Punctuated::parse_terminated)?,
+                    key,
+                )?;
+            } else if key.is_ident("body") {
+                let list = meta.require_list()?;
+                set_once(
+                    &mut body_params,
+                    list.parse_args_with(Punctuated::parse_terminated)?,
+                    key,
+                )?;
+            } else if key.is_ident("form") {
+                let list = meta.require_list()?;
+                set_once(
+                    &mut form_params,
+                    list.parse_args_with(Punctuated::parse_terminated)?,
+                    key,
+                )?;
+            } else if key.is_ident("multipart") {
+                let list = meta.require_list()?;
+                set_once(
+                    &mut multipart_params,
+                    list.parse_args_with(Punctuated::parse_terminated)?,
+                    key,
+                )?;
+            } else if key.is_ident("header") {
+                let list = meta.require_list()?;
+                let params: Punctuated<NamedParam, Token![,]> =
+                    list.parse_args_with(Punctuated::parse_terminated)?;
+                for param in &params {
+                    if !is_token(&param.header_name()) {
+                        return Err(param.error("invalid header name"));
+                    }
                 }
-            } else if meta.path().is_ident("form") {
-                if let syn::Meta::List(list) = meta {
-                    form_params = list.parse_args_with(Punctuated::parse_terminated)?;
+                set_once(&mut header_params, params, key)?;
+            } else if key.is_ident("cookie") {
+                let list = meta.require_list()?;
+                let params: Punctuated<NamedParam, Token![,]> =
+                    list.parse_args_with(Punctuated::parse_terminated)?;
+                for param in &params {
+                    let name = param.cookie_name();
+                    if name.is_empty() || name.contains(|c: char| "=;, \t\"".contains(c)) {
+                        return Err(param.error("invalid cookie name"));
+                    }
                 }
+                set_once(&mut cookie_params, params, key)?;
+            } else if key.is_ident("status") {
+                let nv = meta.require_name_value()?;
+                let lit_int = match &nv.value {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Int(lit_int),
+                        ..
+                    }) if matches!(lit_int.base10_parse::<u16>(), Ok(100..=599)) => lit_int.clone(),
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            &nv.value,
+                            "expected an HTTP status code between 100 and 599, such as `201`",
+                        ))
+                    }
+                };
+                set_once(&mut status, lit_int, key)?;
+            } else if key.is_ident("produces") {
+                let nv = meta.require_name_value()?;
+                let lit_str = match &nv.value {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(lit_str),
+                        ..
+                    }) if is_media_type(&lit_str.value()) => lit_str.clone(),
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            &nv.value,
+                            "expected a media type such as `\"text/plain\"`",
+                        ))
+                    }
+                };
+                set_once(&mut produces, lit_str, key)?;
+            } else {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    "unknown `#[rest]` argument, expected one of `method`, `path`, `query`, `body`, `form`, `multipart`, `header`, `cookie`, `status`, `produces`",
+                ));
             }
         }
 
@@ -101,277 +272,1353 @@ impl Parse for RestAttribute {
             method: method
                 .ok_or_else(|| syn::Error::new(input.span(), "Missing `method` argument"))?,
             path: path.ok_or_else(|| syn::Error::new(input.span(), "Missing `path` argument"))?,
-            query_params,
-            body_params,
-            form_params,
+            query_params: query_params.unwrap_or_default(),
+            body_params: body_params.unwrap_or_default(),
+            form_params: form_params.unwrap_or_default(),
+            multipart_params: multipart_params.unwrap_or_default(),
+            header_params: header_params.unwrap_or_default(),
+            cookie_params: cookie_params.unwrap_or_default(),
+            status,
+            produces,
         })
     }
 }
 
+// Whether `value` is an HTTP token, e.g. a header name.
+fn is_token(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+// Whether `value` is a media type such as `text/plain`, optionally with parameters such as
+// `; charset=utf-8`.
+fn is_media_type(value: &str) -> bool {
+    let essence = value.split(';').next().unwrap_or_default().trim();
+    essence
+        .split_once('/')
+        .is_some_and(|(ty, subtype)| is_token(ty) && is_token(subtype))
+}
+
+// Stores the value of a `#[rest]` argument, rejecting arguments given more than once.
+fn set_once<T>(slot: &mut Option<T>, value: T, key: &syn::Path) -> Result<()> {
+    if slot.is_some() {
+        return Err(syn::Error::new_spanned(
+            key,
+            "argument may only be specified once",
+        ));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+impl RestAttribute {
+    // The media type of a raw response body: `produces`, unless it's JSON, i.e. `application/json`
+    // or a type with a `+json` suffix such as `application/vnd.api+json`.
+    fn raw_body(&self) -> Option<&LitStr> {
+        self.produces.as_ref().filter(|produces| {
+            let essence = produces.value().to_ascii_lowercase();
+            let essence = essence.split(';').next().unwrap_or_default().trim();
+            essence != "application/json" && !essence.ends_with("+json")
+        })
+    }
+
+    // The parameters captured by the path template, e.g. `user_id` for `/users/{user_id}`.
+    // Wildcards such as `{*rest}` are captured under their name without the `*`.
+    fn path_params(&self) -> Result<Vec<Ident>> {
+        self.path
+            .value()
+            .split('/')
+            .filter_map(|s| s.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+            .map(|p| {
+                syn::parse_str::<Ident>(p.trim_start_matches('*'))
+                    .map(|ident| Ident::new(&ident.to_string(), self.path.span()))
+                    .map_err(|_| {
+                        syn::Error::new_spanned(
+                            &self.path,
+                            format!("path parameter `{{{}}}` is not a valid Rust identifier", p),
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    // The path with its parameters unnamed, e.g. `/users/{}` for `/users/{user_id}`. axum
+    // can't tell routes with the same pattern apart.
+    fn path_pattern(&self) -> String {
+        self.path
+            .value()
+            .split('/')
+            .map(|segment| match segment.strip_prefix('{') {
+                Some(param) if param.starts_with('*') => "{*}",
+                Some(_) => "{}",
+                None => segment,
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    // Checks that every argument of `method` is bound to exactly one part of the request.
+    fn validate(&self, method: &ImplItemFn) -> Result<()> {
+        let mut errors = Vec::new();
+        let fn_ident = &method.sig.ident;
+
+        let mut arg_names = Vec::new();
+        for arg in wire_inputs(&method.sig) {
+            if let FnArg::Typed(pt) = arg {
+                match &*pt.pat {
+                    Pat::Ident(pi) => arg_names.push(&pi.ident),
+                    pat => errors.push(syn::Error::new_spanned(
+                        pat,
+                        "arguments of `#[rest]` methods must be plain identifiers",
+                    )),
+                }
+            }
+        }
+
+        // Maps each bound argument to the part of the request it is read from.
+        let mut sources: HashMap<Ident, &str> = HashMap::new();
+
+        for param in self.path_params()? {
+            if !arg_names.contains(&&param) {
+                errors.push(syn::Error::new_spanned(
+                    &self.path,
+                    format!(
+                        "path parameter `{{{}}}` does not match any argument of `{}`",
+                        param, fn_ident
+                    ),
+                ));
+            } else if sources.insert(param.clone(), "the path").is_some() {
+                errors.push(syn::Error::new_spanned(
+                    &self.path,
+                    format!("path parameter `{{{}}}` appears more than once", param),
+                ));
+            }
+        }
+
+        let mapped = |params: &Punctuated<ParamMapping, Token![,]>| -> Vec<Ident> {
+            params.iter().map(|p| p.private_name.clone()).collect()
+        };
+        let named = |params: &Punctuated<NamedParam, Token![,]>| -> Vec<Ident> {
+            params.iter().map(|p| p.private_name.clone()).collect()
+        };
+        let groups = [
+            ("`query(...)`", mapped(&self.query_params)),
+            ("`body(...)`", mapped(&self.body_params)),
+            ("`form(...)`", mapped(&self.form_params)),
+            ("`multipart(...)`", mapped(&self.multipart_params)),
+            ("`header(...)`", named(&self.header_params)),
+            ("`cookie(...)`", named(&self.cookie_params)),
+        ];
+        for (source, params) in groups {
+            for name in &params {
+                if !arg_names.contains(&name) {
+                    errors.push(syn::Error::new_spanned(
+                        name,
+                        format!("`{}` does not match any argument of `{}`", name, fn_ident),
+                    ));
+                } else if let Some(previous) = sources.insert(name.clone(), source) {
+                    errors.push(syn::Error::new_spanned(
+                        name,
+                        format!("argument `{}` is already mapped to {}", name, previous),
+                    ));
+                }
+            }
+        }
+
+        // At most one group can be read from the request body.
+        let body_groups: Vec<_> = [
+            ("`body(...)`", self.body_params.first()),
+            ("`form(...)`", self.form_params.first()),
+            ("`multipart(...)`", self.multipart_params.first()),
+        ]
+        .into_iter()
+        .filter_map(|(source, first)| first.map(|param| (source, param)))
+        .collect();
+        let http_method = self.method.to_string().to_uppercase();
+        if let (Some((source, param)), "GET" | "HEAD") = (body_groups.first(), http_method.as_str())
+        {
+            errors.push(syn::Error::new_spanned(
+                &param.private_name,
+                format!(
+                    "{} can't be used with `{}` requests, which have no body, use `query(...)` instead",
+                    source, http_method
+                ),
+            ));
+        }
+        if let [(first_source, first), rest @ ..] = &body_groups[..] {
+            for (source, param) in rest {
+                let mut err = syn::Error::new_spanned(
+                    &param.private_name,
+                    format!(
+                        "{} and {} cannot be combined, both are read from the request body",
+                        first_source, source
+                    ),
+                );
+                err.combine(syn::Error::new_spanned(
+                    &first.private_name,
+                    format!("{} used here", first_source),
+                ));
+                errors.push(err);
+            }
+        }
+
+        if let Some(produces) = &self.produces {
+            if streaming_item(&method.sig).is_some() {
+                errors.push(syn::Error::new_spanned(
+                    produces,
+                    "streaming methods are served as `text/event-stream`, `produces` can't be set",
+                ));
+            }
+        }
+
+        for name in arg_names {
+            if !sources.contains_key(name) {
+                errors.push(syn::Error::new_spanned(
+                    name,
+                    format!(
+                        "argument `{}` is not mapped to the request, add it to the path or to a `query(...)`, `body(...)`, `form(...)`, `multipart(...)`, `header(...)` or `cookie(...)` group",
+                        name
+                    ),
+                ));
+            }
+        }
+
+        match errors.into_iter().reduce(|mut acc, err| {
+            acc.combine(err);
+            acc
+        }) {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+}
+
 pub struct RestAxum;
 
 impl Protocol for RestAxum {
-    fn transform_trait(&self, _item_trait: &ItemTrait) -> TokenStream {
-        quote! {}
+    fn name(&self) -> &'static str {
+        "rest_axum"
+    }
+
+    fn transform_trait(&self, _item_trait: &ItemTrait) -> Result<TokenStream> {
+        Ok(quote! {})
     }
 
-    fn transform_impl(&self, item_impl: &ItemImpl) -> TokenStream {
-        let self_ty = &item_impl.self_ty;
+    fn transform_impl(&self, item_impl: &ItemImpl, dispatch: &Dispatch) -> Result<TokenStream> {
+        let handle_ty = dispatch.handle_ty(item_impl);
+        let generics = dispatch.generics(item_impl);
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
 
         let mut routes = Vec::new();
         let mut wrapper_structs = Vec::new();
+        #[cfg(feature = "rest-client")]
+        let mut client_methods = Vec::new();
+        #[cfg(feature = "rest-client")]
+        let mut transport_arms = Vec::new();
+        #[cfg(feature = "openapi")]
+        let mut operations = Vec::new();
 
+        // The generated REST client reuses the wrappers to encode its requests.
+        let wrapper_derive = if cfg!(feature = "rest-client") {
+            quote! { #[derive(serde::Deserialize, serde::Serialize)] }
+        } else {
+            quote! { #[derive(serde::Deserialize)] }
+        };
+
+        // Parse and validate every route up front, so that all mistakes are reported at once.
+        let mut rest_methods = Vec::new();
+        let mut errors: Option<syn::Error> = None;
         for item in &item_impl.items {
             if let ImplItem::Fn(method) = item {
                 if let Some(attr) = method.attrs.iter().find(|a| a.path().is_ident("rest")) {
-                    let rest_attr: RestAttribute = match attr.parse_args() {
-                        Ok(attr) => attr,
-                        Err(_) => continue,
-                    };
-
-                    let http_method =
-                        format_ident!("{}", rest_attr.method.to_string().to_lowercase());
-                    let path = &rest_attr.path;
-                    let method_ident = &method.sig.ident;
-
-                    let mut handler_args = vec![];
-
-                    let all_fn_args: HashMap<_, _> = method
-                        .sig
-                        .inputs
-                        .iter()
-                        .skip(1)
-                        .filter_map(|arg| {
-                            if let FnArg::Typed(pt) = arg {
-                                if let Pat::Ident(pi) = &*pt.pat {
-                                    return Some((pi.ident.clone(), &pt.ty));
-                                }
-                            }
-                            None
-                        })
-                        .collect();
-
-                    let ordered_fn_arg_names: Vec<_> = method
-                        .sig
-                        .inputs
-                        .iter()
-                        .skip(1)
-                        .filter_map(|arg| {
-                            if let FnArg::Typed(pt) = arg {
-                                if let Pat::Ident(pi) = &*pt.pat {
-                                    return Some(pi.ident.clone());
-                                }
-                            }
-                            None
-                        })
-                        .collect();
-
-                    let path_str = path.value();
-                    let path_params: Vec<_> = path_str
-                        .split('/')
-                        .filter_map(|s| s.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
-                        .map(|p| format_ident!("{}", p))
-                        .collect();
-
-                    let path_params_set: HashSet<_> = path_params.iter().cloned().collect();
-                    let query_params_set: HashSet<_> = rest_attr
-                        .query_params
-                        .iter()
-                        .map(|p| p.private_name.clone())
-                        .collect();
-                    let body_params_set: HashSet<_> = rest_attr
-                        .body_params
-                        .iter()
-                        .map(|p| p.private_name.clone())
-                        .collect();
-                    let form_params_set: HashSet<_> = rest_attr
-                        .form_params
-                        .iter()
-                        .map(|p| p.private_name.clone())
-                        .collect();
-
-                    if !path_params.is_empty() {
-                        let method_name_str = method_ident.to_string();
-                        let mut pascal_case_name = String::new();
-                        let mut capitalize = true;
-                        for c in method_name_str.chars() {
-                            if c == '_' {
-                                capitalize = true;
-                            } else if capitalize {
-                                pascal_case_name.push(c.to_ascii_uppercase());
-                                capitalize = false;
-                            } else {
-                                pascal_case_name.push(c);
-                            }
-                        }
-                        let path_wrapper_ident = format_ident!("{}PathParams", pascal_case_name);
+                    let parsed = attr
+                        .parse_args::<RestAttribute>()
+                        .and_then(|rest_attr| rest_attr.validate(method).map(|_| rest_attr));
+                    match parsed {
+                        Ok(rest_attr) => rest_methods.push((method, rest_attr)),
+                        Err(err) => match &mut errors {
+                            Some(errors) => errors.combine(err),
+                            None => errors = Some(err),
+                        },
+                    }
+                }
+            }
+        }
 
-                        let mut path_fields = vec![];
-                        for p_param in &path_params {
-                            let param_ty = all_fn_args.get(p_param).unwrap();
-                            path_fields.push(quote! { pub #p_param: #param_ty });
-                        }
+        // axum panics when the router is built from conflicting routes, so report them here.
+        let mut patterns: HashMap<String, &RestAttribute> = HashMap::new();
+        let mut handlers: HashMap<(String, String), &Ident> = HashMap::new();
+        for (method, rest_attr) in &rest_methods {
+            let pattern = rest_attr.path_pattern();
+            let http_method = rest_attr.method.to_string().to_uppercase();
+            let conflict = match patterns.get(&pattern) {
+                Some(other) if other.path.value() != rest_attr.path.value() => Some(format!(
+                    "path `{}` conflicts with `{}`, parameters at the same position must have the same name",
+                    rest_attr.path.value(),
+                    other.path.value()
+                )),
+                _ => handlers
+                    .get(&(http_method.clone(), pattern.clone()))
+                    .map(|previous| {
+                        format!(
+                            "route `{} {}` is already served by `{}`",
+                            http_method,
+                            rest_attr.path.value(),
+                            previous
+                        )
+                    }),
+            };
+            if let Some(message) = conflict {
+                let err = syn::Error::new_spanned(&rest_attr.path, message);
+                match &mut errors {
+                    Some(errors) => errors.combine(err),
+                    None => errors = Some(err),
+                }
+                continue;
+            }
+            patterns.entry(pattern.clone()).or_insert(rest_attr);
+            handlers.insert((http_method, pattern), &method.sig.ident);
+        }
+        if let Some(err) = errors {
+            return Err(err);
+        }
 
-                        wrapper_structs.push(quote! {
-                            #[derive(serde::Deserialize)]
-                            pub struct #path_wrapper_ident {
-                                #(#path_fields),*
-                            }
-                        });
+        for (method, rest_attr) in rest_methods {
+            let http_method = format_ident!("{}", rest_attr.method.to_string().to_lowercase());
+            let path = &rest_attr.path;
+            let method_ident = &method.sig.ident;
 
-                        handler_args.push(quote! { axum::extract::Path(path_params): axum::extract::Path<#path_wrapper_ident> });
-                    }
+            let mut handler_args = vec![];
+            let mut extractions = vec![];
 
-                    if !rest_attr.query_params.is_empty() {
-                        let query_wrapper_ident =
-                            format_ident!("{}Query", method_ident.to_string());
-                        let mut query_wrapper_fields = vec![];
-                        for q_param in &rest_attr.query_params {
-                            let pub_name_str = q_param.public_name.to_string();
-                            let priv_name = &q_param.private_name;
-                            let arg_ty = all_fn_args.get(priv_name).unwrap();
-                            query_wrapper_fields.push(
-                                quote! { #[serde(rename = #pub_name_str)] pub #priv_name: #arg_ty },
-                            );
+            let all_fn_args: HashMap<_, _> = wire_inputs(&method.sig)
+                .filter_map(|arg| {
+                    if let FnArg::Typed(pt) = arg {
+                        if let Pat::Ident(pi) = &*pt.pat {
+                            return Some((pi.ident.clone(), &pt.ty));
                         }
-                        handler_args.push(quote! { axum::extract::Query(query_params): axum::extract::Query<#query_wrapper_ident> });
-                        wrapper_structs.push(quote! {
-                            #[derive(serde::Deserialize)]
-                            pub struct #query_wrapper_ident {
-                                #(#query_wrapper_fields),*
-                            }
-                        });
                     }
+                    None
+                })
+                .collect();
 
-                    if !rest_attr.body_params.is_empty() {
-                        let body_wrapper_ident = format_ident!("{}Body", method_ident.to_string());
-                        let mut body_wrapper_fields = vec![];
-                        for b_param in &rest_attr.body_params {
-                            let pub_name_str = b_param.public_name.to_string();
-                            let priv_name = &b_param.private_name;
-                            let arg_ty = all_fn_args.get(priv_name).unwrap();
-                            body_wrapper_fields.push(
-                                quote! { #[serde(rename = #pub_name_str)] pub #priv_name: #arg_ty },
-                            );
-                        }
-                        handler_args.push(quote! { axum::extract::Json(body_params): axum::extract::Json<#body_wrapper_ident> });
-                        wrapper_structs.push(quote! {
-                            #[derive(serde::Deserialize)]
-                            pub struct #body_wrapper_ident {
-                                #(#body_wrapper_fields),*
-                            }
-                        });
-                    }
+            let path_params = rest_attr.path_params()?;
 
-                    if !rest_attr.form_params.is_empty() {
-                        let form_wrapper_ident = format_ident!("{}Form", method_ident.to_string());
-                        let mut form_wrapper_fields = vec![];
-                        for f_param in &rest_attr.form_params {
-                            let pub_name_str = f_param.public_name.to_string();
-                            let priv_name = &f_param.private_name;
-                            let arg_ty = all_fn_args.get(priv_name).unwrap();
-                            form_wrapper_fields.push(
-                                quote! { #[serde(rename = #pub_name_str)] pub #priv_name: #arg_ty },
-                            );
-                        }
-                        handler_args.push(quote! { axum::extract::Form(form_params): axum::extract::Form<#form_wrapper_ident> });
-                        wrapper_structs.push(quote! {
-                            #[derive(serde::Deserialize)]
-                            pub struct #form_wrapper_ident {
-                                #(#form_wrapper_fields),*
-                            }
-                        });
+            let path_params_set: HashSet<_> = path_params.iter().cloned().collect();
+            let query_params_set: HashSet<_> = rest_attr
+                .query_params
+                .iter()
+                .map(|p| p.private_name.clone())
+                .collect();
+            let body_params_set: HashSet<_> = rest_attr
+                .body_params
+                .iter()
+                .map(|p| p.private_name.clone())
+                .collect();
+            let form_params_set: HashSet<_> = rest_attr
+                .form_params
+                .iter()
+                .map(|p| p.private_name.clone())
+                .collect();
+
+            if !path_params.is_empty() {
+                let pascal_case_name = to_pascal_case(&method_ident.to_string());
+                let path_wrapper_ident = format_ident!("{}PathParams", pascal_case_name);
+
+                let mut path_fields = vec![];
+                for p_param in &path_params {
+                    let param_ty = all_fn_args.get(p_param).unwrap();
+                    path_fields.push(quote! { pub #p_param: #param_ty });
+                }
+
+                wrapper_structs.push(quote! {
+                    #wrapper_derive
+                    pub struct #path_wrapper_ident {
+                        #(#path_fields),*
                     }
+                });
 
-                    let mut call_args = vec![];
-                    for arg_name in &ordered_fn_arg_names {
-                        if path_params_set.contains(arg_name) {
-                            call_args.push(quote! { path_params.#arg_name });
-                        } else if query_params_set.contains(arg_name) {
-                            call_args.push(quote! { query_params.#arg_name });
-                        } else if body_params_set.contains(arg_name) {
-                            call_args.push(quote! { body_params.#arg_name });
-                        } else if form_params_set.contains(arg_name) {
-                            call_args.push(quote! { form_params.#arg_name });
-                        }
+                extract(
+                    &mut handler_args,
+                    &mut extractions,
+                    quote! { Path },
+                    quote! { PathRejection },
+                    format_ident!("path_params"),
+                    &path_wrapper_ident,
+                );
+            }
+
+            if !rest_attr.query_params.is_empty() {
+                let query_wrapper_ident = format_ident!("{}Query", method_ident.to_string());
+                let mut query_wrapper_fields = vec![];
+                for q_param in &rest_attr.query_params {
+                    let pub_name_str = q_param.public_name.to_string();
+                    let priv_name = &q_param.private_name;
+                    let arg_ty = all_fn_args.get(priv_name).unwrap();
+                    query_wrapper_fields
+                        .push(quote! { #[serde(rename = #pub_name_str)] pub #priv_name: #arg_ty });
+                }
+                extract(
+                    &mut handler_args,
+                    &mut extractions,
+                    quote! { Query },
+                    quote! { QueryRejection },
+                    format_ident!("query_params"),
+                    &query_wrapper_ident,
+                );
+                wrapper_structs.push(quote! {
+                    #wrapper_derive
+                    pub struct #query_wrapper_ident {
+                        #(#query_wrapper_fields),*
                     }
+                });
+            }
 
-                    let method_call =
-                        quote! { service.lock().await.#method_ident(#(#call_args),*).await };
+            if !rest_attr.body_params.is_empty() {
+                let body_wrapper_ident = format_ident!("{}Body", method_ident.to_string());
+                let mut body_wrapper_fields = vec![];
+                for b_param in &rest_attr.body_params {
+                    let pub_name_str = b_param.public_name.to_string();
+                    let priv_name = &b_param.private_name;
+                    let arg_ty = all_fn_args.get(priv_name).unwrap();
+                    body_wrapper_fields
+                        .push(quote! { #[serde(rename = #pub_name_str)] pub #priv_name: #arg_ty });
+                }
+                match optional_fields(&rest_attr.body_params, &all_fn_args) {
+                    Some(fields) => extract_optional_body(
+                        &mut handler_args,
+                        &mut extractions,
+                        quote! { Json },
+                        quote! { JsonRejection },
+                        format_ident!("body_params"),
+                        &body_wrapper_ident,
+                        &fields,
+                    ),
+                    None => extract(
+                        &mut handler_args,
+                        &mut extractions,
+                        quote! { Json },
+                        quote! { JsonRejection },
+                        format_ident!("body_params"),
+                        &body_wrapper_ident,
+                    ),
+                }
+                wrapper_structs.push(quote! {
+                    #wrapper_derive
+                    pub struct #body_wrapper_ident {
+                        #(#body_wrapper_fields),*
+                    }
+                });
+            }
 
-                    let mut is_result = false;
-                    if let ReturnType::Type(_, ty) = &method.sig.output {
-                        if let Type::Path(type_path) = &**ty {
-                            if let Some(segment) = type_path.path.segments.last() {
-                                if segment.ident == "Result" {
-                                    is_result = true;
-                                }
-                            }
-                        }
+            if !rest_attr.form_params.is_empty() {
+                let form_wrapper_ident = format_ident!("{}Form", method_ident.to_string());
+                let mut form_wrapper_fields = vec![];
+                for f_param in &rest_attr.form_params {
+                    let pub_name_str = f_param.public_name.to_string();
+                    let priv_name = &f_param.private_name;
+                    let arg_ty = all_fn_args.get(priv_name).unwrap();
+                    form_wrapper_fields
+                        .push(quote! { #[serde(rename = #pub_name_str)] pub #priv_name: #arg_ty });
+                }
+                match optional_fields(&rest_attr.form_params, &all_fn_args) {
+                    Some(fields) => extract_optional_body(
+                        &mut handler_args,
+                        &mut extractions,
+                        quote! { Form },
+                        quote! { FormRejection },
+                        format_ident!("form_params"),
+                        &form_wrapper_ident,
+                        &fields,
+                    ),
+                    None => extract(
+                        &mut handler_args,
+                        &mut extractions,
+                        quote! { Form },
+                        quote! { FormRejection },
+                        format_ident!("form_params"),
+                        &form_wrapper_ident,
+                    ),
+                }
+                wrapper_structs.push(quote! {
+                    #wrapper_derive
+                    pub struct #form_wrapper_ident {
+                        #(#form_wrapper_fields),*
                     }
+                });
+            }
 
-                    let handler_body = if is_result {
-                        quote! {
-                            match #method_call {
-                                Ok(result) => axum::response::Json(result).into_response(),
-                                Err(e) => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
-                            }
-                        }
+            // Headers, cookies and multipart fields are parsed into local variables, an `Option`
+            // argument being `None` if it's missing.
+            let mut named_bindings = HashMap::new();
+            let named_groups = [
+                (&rest_attr.header_params, "header"),
+                (&rest_attr.cookie_params, "cookie"),
+            ];
+            for (params, kind) in named_groups {
+                for param in params {
+                    let priv_name = &param.private_name;
+                    let arg_ty = all_fn_args.get(priv_name).unwrap();
+                    let name = if kind == "header" {
+                        param.header_name()
+                    } else {
+                        param.cookie_name()
+                    };
+                    let description = format!("{} `{}`", kind, name);
+                    let binding = format_ident!("{}_{}", kind, priv_name);
+                    let parse = format_ident!("{}", kind);
+                    let value = if is_option(arg_ty) {
+                        quote! { multi_rpc::rest_axum::#parse(&headers, #name) }
                     } else {
                         quote! {
-                            let result = #method_call;
-                            axum::response::Json(result).into_response()
+                            multi_rpc::rest_axum::#parse(&headers, #name)
+                                .and_then(|value| multi_rpc::rest_axum::required(value, #description))
                         }
                     };
+                    extractions.push(quote! {
+                        let #binding: #arg_ty = match #value {
+                            Ok(value) => value,
+                            Err(problem) => return problem.into_response(),
+                        };
+                    });
+                    named_bindings.insert(priv_name.clone(), binding);
+                }
+            }
+            if !named_bindings.is_empty() {
+                // Extracted before the body, which consumes the request.
+                handler_args.insert(0, quote! { headers: axum::http::HeaderMap });
+            }
 
-                    let handler_args_punctuated =
-                        Punctuated::<_, Token![,]>::from_iter(handler_args);
-
-                    let handler_args_with_comma = if handler_args_punctuated.is_empty() {
-                        quote! {}
-                    } else {
-                        quote! { , #handler_args_punctuated }
+            // Multipart fields are read into memory, then `Vec<u8>` and `Bytes` arguments receive
+            // the contents of files and other arguments are deserialized from text fields.
+            if !rest_attr.multipart_params.is_empty() {
+                // Without a required field, a request without a body has no fields.
+                let (multipart_ty, multipart_pat, no_body) =
+                    match optional_fields(&rest_attr.multipart_params, &all_fn_args) {
+                        Some(_) => (
+                            quote! { multi_rpc::rest_axum::OptionalBody<axum::extract::Multipart> },
+                            quote! { multi_rpc::rest_axum::OptionalBody(Some(multipart)) },
+                            Some(quote! {
+                                Ok(multi_rpc::rest_axum::OptionalBody(None)) => {
+                                    multi_rpc::rest_axum::MultipartFields::default()
+                                }
+                            }),
+                        ),
+                        None => (
+                            quote! { axum::extract::Multipart },
+                            quote! { multipart },
+                            None,
+                        ),
+                    };
+                handler_args.push(quote! {
+                    multipart: Result<#multipart_ty, axum::extract::multipart::MultipartRejection>
+                });
+                extractions.push(quote! {
+                    let multipart_fields = match multipart {
+                        Ok(#multipart_pat) => match multi_rpc::rest_axum::MultipartFields::read(multipart).await {
+                            Ok(multipart_fields) => multipart_fields,
+                            Err(problem) => return problem.into_response(),
+                        },
+                        #no_body
+                        Err(rejection) => {
+                            return multi_rpc::problem::Problem::new(
+                                rejection.status().as_u16(),
+                                rejection.body_text(),
+                            )
+                            .into_response();
+                        }
+                    };
+                });
+            }
+            for param in &rest_attr.multipart_params {
+                let priv_name = &param.private_name;
+                let name = param.public_name.to_string();
+                let arg_ty = all_fn_args.get(priv_name).unwrap();
+                let description = format!("multipart field `{}`", name);
+                let binding = format_ident!("multipart_{}", priv_name);
+                let read = if is_bytes(option_inner_ty(arg_ty).unwrap_or(arg_ty)) {
+                    quote! { file }
+                } else {
+                    quote! { text }
+                };
+                let value = if is_option(arg_ty) {
+                    quote! { multipart_fields.#read(#name) }
+                } else {
+                    quote! {
+                        multipart_fields.#read(#name)
+                            .and_then(|value| multi_rpc::rest_axum::required(value, #description))
+                    }
+                };
+                extractions.push(quote! {
+                    let #binding: #arg_ty = match #value {
+                        Ok(value) => value,
+                        Err(problem) => return problem.into_response(),
                     };
+                });
+                named_bindings.insert(priv_name.clone(), binding);
+            }
 
-                    routes.push(quote! {
-                        .route(#path, axum::routing::#http_method(|
-                            axum::extract::State(service): axum::extract::State<std::sync::Arc<tokio::sync::Mutex<#self_ty>>>
-                            #handler_args_with_comma
-                        | async move {
-                            use axum::response::IntoResponse;
-                            #handler_body
-                        }))
-                    });
+            let mut call_args = vec![];
+            for arg in &method.sig.inputs {
+                let FnArg::Typed(pt) = arg else {
+                    continue;
+                };
+                if is_context_arg(arg) {
+                    call_args.push(quote! { request_context });
+                    continue;
                 }
+                let Pat::Ident(pi) = &*pt.pat else {
+                    continue;
+                };
+                let arg_name = &pi.ident;
+                if path_params_set.contains(arg_name) {
+                    call_args.push(quote! { path_params.#arg_name });
+                } else if query_params_set.contains(arg_name) {
+                    call_args.push(quote! { query_params.#arg_name });
+                } else if body_params_set.contains(arg_name) {
+                    call_args.push(quote! { body_params.#arg_name });
+                } else if form_params_set.contains(arg_name) {
+                    call_args.push(quote! { form_params.#arg_name });
+                } else if let Some(binding) = named_bindings.get(arg_name) {
+                    call_args.push(quote! { #binding });
+                }
+            }
+
+            #[cfg(feature = "openapi")]
+            operations.push(openapi_operation(method, &rest_attr)?);
+            #[cfg(feature = "rest-client")]
+            {
+                client_methods.push(client_method(method, &rest_attr));
+                transport_arms.extend(transport_arm(method));
             }
+
+            let on_actor_error = quote! {
+                return multi_rpc::problem::Problem::new(
+                    multi_rpc::error::ProtocolError::http_status(&err),
+                    err.to_string(),
+                )
+                .into_response()
+            };
+            let method_call =
+                dispatch.call(&quote! { service }, method, &call_args, &on_actor_error);
+
+            let (value_ty, is_result) = match &method.sig.output {
+                ReturnType::Default => (None, false),
+                ReturnType::Type(_, ty) => match result_ok_ty(ty) {
+                    Some(ok_ty) => (Some(ok_ty), true),
+                    None => (Some(&**ty), false),
+                },
+            };
+
+            // `RestResponse` and other `IntoRestResponse` values set their own status and
+            // headers, falling back to the route's status.
+            let status = match &rest_attr.status {
+                Some(status) => quote! { axum::http::StatusCode::from_u16(#status).unwrap() },
+                None => quote! { axum::http::StatusCode::OK },
+            };
+            let respond = match rest_attr.raw_body() {
+                Some(produces) if value_ty.is_some_and(is_rest_response) => quote! {
+                    result.into_rest_axum_raw_response(#status, #produces)
+                },
+                Some(produces) => quote! {
+                    multi_rpc::rest_axum::raw_response(#status, #produces, result)
+                },
+                None => {
+                    let response = quote! {{
+                        use multi_rpc::rest_axum::MapIntoRestResponse as _;
+                        use multi_rpc::rest_axum::MapJsonResponse as _;
+                        multi_rpc::rest_axum::ResponseMapping(result).into_rest_response(#status)
+                    }};
+                    // JSON bodies are sent as the JSON media type the route `produces`, if any.
+                    match &rest_attr.produces {
+                        Some(produces) => quote! {
+                            multi_rpc::rest_axum::with_json_content_type(#response, #produces)
+                        },
+                        None => response,
+                    }
+                }
+            };
+
+            let handler_body = if streaming_item(&method.sig).is_some() {
+                // Streaming methods are served as Server-Sent Events, one JSON `data` per item.
+                quote! {
+                    let stream = futures::StreamExt::map(#method_call, |item| {
+                        axum::response::sse::Event::default().json_data(item)
+                    });
+                    let sse = axum::response::sse::Sse::new(stream)
+                        .keep_alive(axum::response::sse::KeepAlive::default());
+                    (#status, sse).into_response()
+                }
+            } else if is_result {
+                quote! {
+                    match #method_call {
+                        Ok(result) => #respond,
+                        Err(e) => {
+                            use multi_rpc::error::MapOtherError as _;
+                            use multi_rpc::error::MapProtocolError as _;
+                            let mapping = multi_rpc::error::ErrorMapping(&e);
+                            multi_rpc::problem::Problem::new((&mapping).http_status(), e.to_string())
+                                .with_data((&mapping).data())
+                                .into_response()
+                        }
+                    }
+                }
+            } else {
+                quote! {
+                    let result = #method_call;
+                    #respond
+                }
+            };
+
+            // The request parts are extracted before the body, which consumes the request.
+            let context = if takes_context(&method.sig) {
+                handler_args.insert(0, quote! { parts: axum::http::request::Parts });
+                quote! {
+                    let request_context = multi_rpc::context::RequestContext::from_rest_axum(&parts);
+                }
+            } else {
+                quote! {}
+            };
+
+            let handler_args_punctuated = Punctuated::<_, Token![,]>::from_iter(handler_args);
+
+            let handler_args_with_comma = if handler_args_punctuated.is_empty() {
+                quote! {}
+            } else {
+                quote! { , #handler_args_punctuated }
+            };
+
+            routes.push(quote! {
+                .route(#path, axum::routing::#http_method(|
+                    axum::extract::State(service): axum::extract::State<#handle_ty>
+                    #handler_args_with_comma
+                | async move {
+                    use axum::response::IntoResponse;
+                    #(#extractions)*
+                    #context
+                    #handler_body
+                }))
+            });
         }
 
-        quote! {
+        let turbofish = generics.split_for_impl().1.as_turbofish();
+
+        #[cfg(feature = "rest-client")]
+        let client = rest_client(item_impl, &client_methods, &transport_arms);
+        #[cfg(not(feature = "rest-client"))]
+        let client = quote! {};
+
+        #[cfg(feature = "openapi")]
+        let openapi = openapi(item_impl, dispatch, &operations);
+        #[cfg(not(feature = "openapi"))]
+        let openapi = quote! {};
+
+        // With only the `rest-client` feature, the impl module holds the client without the server.
+        let server = if cfg!(feature = "rest-axum") {
+            quote! {
+                /// The routes of the REST server, for serving them with additional middleware, e.g. an
+                /// authentication layer inserting a `multi_rpc::context::Identity` into the request
+                /// extensions. Serve the router with `into_make_service_with_connect_info::<SocketAddr>()`
+                /// to provide the client's address to the `RequestContext`.
+                ///
+                /// The router has no fallbacks, so it can be merged with other routers. Add
+                /// `multi_rpc::rest_axum::problem_fallbacks` to answer unknown routes and methods with
+                /// problem details, as the `rest_axum` server does.
+                pub fn rest_axum_router #impl_generics () -> axum::Router<#handle_ty> #where_clause {
+                    use self::rest_axum_wrappers::*;
+
+                    axum::Router::new()
+                        #(#routes)*
+                }
+
+                async fn serve_rest_axum(addr: std::net::SocketAddr, app: axum::Router) {
+                    let app = multi_rpc::rest_axum::problem_fallbacks(app);
+                    println!("🌐 REST (Axum) server listening on http://{}", addr);
+                    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+                    axum::serve(
+                        listener,
+                        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+                    )
+                    .await
+                    .unwrap();
+                }
+
+                pub fn rest_axum #impl_generics (addr: std::net::SocketAddr)
+                    -> impl FnOnce(#handle_ty) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>
+                #where_clause
+                {
+                    move |service| {
+                        let app = rest_axum_router #turbofish ().with_state(service);
+                        Box::pin(serve_rest_axum(addr, app))
+                    }
+                }
+            }
+        } else {
+            quote! {}
+        };
+
+        Ok(quote! {
             pub mod rest_axum_wrappers {
                 use super::*;
                 use serde::{Deserialize};
                 #(#wrapper_structs)*
             }
 
-            pub fn rest_axum(addr: std::net::SocketAddr)
-                -> impl FnOnce(std::sync::Arc<tokio::sync::Mutex<#self_ty>>) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>
-            {
-                use self::rest_axum_wrappers::*;
-                use std::sync::Arc;
-                use tokio::sync::Mutex;
-
-                move |service| {
-                    Box::pin(async move {
-                        let app = axum::Router::new()
-                            #(#routes)*
-                            .with_state(service);
-
-                        println!("🌐 REST (Axum) server listening on http://{}", addr);
-                        let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-                        axum::serve(listener, app.into_make_service()).await.unwrap();
-                    })
+            #client
+
+            #server
+
+            #openapi
+        })
+    }
+}
+
+// Whether `ty` is `multi_rpc::RestResponse<T>`, recognized by its last path segment, which has
+// its own raw response.
+fn is_rest_response(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "RestResponse"),
+        _ => false,
+    }
+}
+
+// Adds the handler argument extracting `binding` with the axum extractor `kind` into `wrapper`,
+// and the statement unwrapping it, which responds to a rejection with its problem details.
+fn extract(
+    handler_args: &mut Vec<TokenStream>,
+    extractions: &mut Vec<TokenStream>,
+    kind: TokenStream,
+    rejection: TokenStream,
+    binding: Ident,
+    wrapper: &Ident,
+) {
+    handler_args.push(quote! {
+        #binding: Result<axum::extract::#kind<#wrapper>, axum::extract::rejection::#rejection>
+    });
+    extractions.push(quote! {
+        let axum::extract::#kind(#binding) = match #binding {
+            Ok(extracted) => extracted,
+            Err(rejection) => {
+                return multi_rpc::problem::Problem::new(
+                    rejection.status().as_u16(),
+                    rejection.body_text(),
+                )
+                .into_response();
+            }
+        };
+    });
+}
+
+// Like `extract`, for a request body whose fields are all `Option`s: a request without a body
+// passes `None` for every field.
+fn extract_optional_body(
+    handler_args: &mut Vec<TokenStream>,
+    extractions: &mut Vec<TokenStream>,
+    kind: TokenStream,
+    rejection: TokenStream,
+    binding: Ident,
+    wrapper: &Ident,
+    fields: &[&Ident],
+) {
+    handler_args.push(quote! {
+        #binding: Result<
+            multi_rpc::rest_axum::OptionalBody<axum::extract::#kind<#wrapper>>,
+            axum::extract::rejection::#rejection,
+        >
+    });
+    extractions.push(quote! {
+        let #binding = match #binding {
+            Ok(multi_rpc::rest_axum::OptionalBody(Some(axum::extract::#kind(extracted)))) => extracted,
+            Ok(multi_rpc::rest_axum::OptionalBody(None)) => #wrapper { #(#fields: None),* },
+            Err(rejection) => {
+                return multi_rpc::problem::Problem::new(
+                    rejection.status().as_u16(),
+                    rejection.body_text(),
+                )
+                .into_response();
+            }
+        };
+    });
+}
+
+// The arguments bound to `params` if they are all `Option`s, so that the request body may be
+// left out, or else `None`.
+fn optional_fields<'a, T: AsRef<Type>>(
+    params: &'a Punctuated<ParamMapping, Token![,]>,
+    arg_tys: &HashMap<Ident, T>,
+) -> Option<Vec<&'a Ident>> {
+    params
+        .iter()
+        .all(|param| is_option(arg_tys[&param.private_name].as_ref()))
+        .then(|| params.iter().map(|param| &param.private_name).collect())
+}
+
+// Generates the `{Trait}RestClient` holding the client `methods`, and its `Transport` impl
+// dispatching to them by name.
+#[cfg(feature = "rest-client")]
+fn rest_client(
+    item_impl: &ItemImpl,
+    methods: &[TokenStream],
+    transport_arms: &[TokenStream],
+) -> TokenStream {
+    let trait_ident = &item_impl
+        .trait_
+        .as_ref()
+        .unwrap()
+        .1
+        .segments
+        .last()
+        .unwrap()
+        .ident;
+    let client_ident = format_ident!("{}RestClient", trait_ident);
+    let doc = format!(
+        "A typed client for the REST endpoints of `{}`.",
+        trait_ident
+    );
+
+    quote! {
+        #[doc = #doc]
+        #[derive(Clone, Debug)]
+        pub struct #client_ident(multi_rpc::client::rest::RestClient);
+
+        impl #client_ident {
+            /// Creates a client for the server at `base_url`, e.g. `http://127.0.0.1:9002`.
+            pub fn new(base_url: impl Into<String>) -> Self {
+                Self(multi_rpc::client::rest::RestClient::new(base_url))
+            }
+
+            /// Creates a client from a configured `RestClient`.
+            pub fn from_client(client: multi_rpc::client::rest::RestClient) -> Self {
+                Self(client)
+            }
+
+            #(#methods)*
+        }
+
+        impl multi_rpc::client::Transport for #client_ident {
+            fn call<'a>(
+                &'a self,
+                method: &'a str,
+                params: Vec<serde_json::Value>,
+            ) -> futures::future::BoxFuture<'a, Result<serde_json::Value, multi_rpc::client::ClientError>> {
+                Box::pin(async move {
+                    match method {
+                        #(#transport_arms)*
+                        _ => Err(multi_rpc::client::ClientError::UnsupportedMethod(method.to_string())),
+                    }
+                })
+            }
+        }
+    }
+}
+
+// Generates the `Transport` match arm that calls the client method for `method`.
+//
+// Streaming methods can't be called through a `Transport`, so they have none.
+#[cfg(feature = "rest-client")]
+fn transport_arm(method: &ImplItemFn) -> Option<TokenStream> {
+    if streaming_item(&method.sig).is_some() {
+        return None;
+    }
+    let method_ident = &method.sig.ident;
+    let method_name = method_ident.to_string();
+    let (args, arg_tys): (Vec<_>, Vec<_>) = wire_inputs(&method.sig)
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pt) => Some(&pt.ty),
+            FnArg::Receiver(_) => None,
+        })
+        .enumerate()
+        .map(|(i, ty)| (format_ident!("arg{}", i), ty))
+        .unzip();
+
+    Some(quote! {
+        #method_name => {
+            let mut params = params.into_iter();
+            #(let #args: #arg_tys = multi_rpc::client::param(&mut params)?;)*
+            let response = self.#method_ident(#(#args),*).await?;
+            multi_rpc::client::encode(&response)
+        }
+    })
+}
+
+// Generates the client method that sends the request the route of `method` expects.
+//
+// The method takes the arguments of the service method and returns its value, or for methods
+// returning `Result<T, E>` the `T`, with failures reported as `ClientError`.
+#[cfg(feature = "rest-client")]
+fn client_method(method: &ImplItemFn, rest_attr: &RestAttribute) -> TokenStream {
+    let method_ident = &method.sig.ident;
+    let http_method = rest_attr.method.to_string();
+    let inputs = wire_inputs(&method.sig);
+
+    // Substitute the path parameters into the path template.
+    let mut path_args = Vec::new();
+    let path_format = rest_attr
+        .path
+        .value()
+        .split('/')
+        .map(
+            |segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(param) => {
+                    let (encode, name) = match param.strip_prefix('*') {
+                        Some(name) => (quote! { path_wildcard }, name),
+                        None => (quote! { path_segment }, param),
+                    };
+                    let ident = format_ident!("{}", name);
+                    path_args.push(quote! { multi_rpc::client::rest::#encode(&#ident)? });
+                    "{}".to_string()
                 }
+                None => segment.to_string(),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join("/");
+
+    let mut request = quote! { self.0.request(#http_method, &path) };
+    let groups = [
+        (&rest_attr.query_params, "Query", quote! { query }),
+        (&rest_attr.body_params, "Body", quote! { json }),
+        (&rest_attr.form_params, "Form", quote! { form }),
+    ];
+    for (params, suffix, encode) in groups {
+        if !params.is_empty() {
+            let wrapper_ident = format_ident!("{}{}", method_ident.to_string(), suffix);
+            let fields = params.iter().map(|p| &p.private_name);
+            request = quote! {
+                #request.#encode(&rest_axum_wrappers::#wrapper_ident { #(#fields),* })
+            };
+        }
+    }
+
+    let arg_tys: HashMap<_, _> = wire_inputs(&method.sig)
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pt) => match &*pt.pat {
+                Pat::Ident(pi) => Some((pi.ident.clone(), &*pt.ty)),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+    let multipart = (!rest_attr.multipart_params.is_empty()).then(|| {
+        let fields = rest_attr.multipart_params.iter().map(|param| {
+            let name = param.public_name.to_string();
+            let arg = &param.private_name;
+            let ty = arg_tys[arg];
+            match option_inner_ty(ty) {
+                Some(inner_ty) if is_bytes(inner_ty) => quote! {
+                    let form = multi_rpc::client::rest::multipart_file(form, #name, #arg.as_ref());
+                },
+                _ if is_bytes(ty) => quote! {
+                    let form = multi_rpc::client::rest::multipart_file(form, #name, Some(&#arg));
+                },
+                _ => quote! {
+                    let form = multi_rpc::client::rest::multipart_text(form, #name, &#arg)?;
+                },
+            }
+        });
+        quote! {
+            let form = multi_rpc::reqwest::multipart::Form::new();
+            #(#fields)*
+            let request = request.multipart(form);
+        }
+    });
+    let headers = rest_attr.header_params.iter().map(|param| {
+        let name = param.header_name();
+        let arg = &param.private_name;
+        quote! { let request = multi_rpc::client::rest::with_header(request, #name, &#arg)?; }
+    });
+    let cookies = (!rest_attr.cookie_params.is_empty()).then(|| {
+        let cookies = rest_attr.cookie_params.iter().map(|param| {
+            let name = param.cookie_name();
+            let arg = &param.private_name;
+            quote! { multi_rpc::client::rest::cookie(#name, &#arg)? }
+        });
+        quote! {
+            let request = multi_rpc::client::rest::with_cookies(request, vec![#(#cookies),*]);
+        }
+    });
+
+    let (output, send) = if let Some(item_ty) = streaming_item(&method.sig) {
+        (
+            quote! { multi_rpc::Streaming<Result<#item_ty, multi_rpc::client::ClientError>> },
+            quote! { send_sse },
+        )
+    } else {
+        let output = match &method.sig.output {
+            ReturnType::Default => quote! { () },
+            ReturnType::Type(_, ty) => match result_ok_ty(ty) {
+                Some(ok_ty) => quote! { #ok_ty },
+                None => quote! { #ty },
+            },
+        };
+        let send = match rest_attr.raw_body() {
+            Some(_) => quote! { send_raw },
+            None => quote! { send_json },
+        };
+        (output, send)
+    };
+
+    quote! {
+        pub async fn #method_ident(&self, #(#inputs),*) -> Result<#output, multi_rpc::client::ClientError> {
+            let path = format!(#path_format, #(#path_args),*);
+            let request = #request;
+            #multipart
+            #(#headers)*
+            #cookies
+            multi_rpc::client::rest::#send(request).await
+        }
+    }
+}
+
+// Generates `openapi()`, which describes the routes through their `operations`, and the
+// `rest_axum_with_openapi` server factory, which also serves the document at `/openapi.json`.
+#[cfg(feature = "openapi")]
+fn openapi(item_impl: &ItemImpl, dispatch: &Dispatch, operations: &[TokenStream]) -> TokenStream {
+    let handle_ty = dispatch.handle_ty(item_impl);
+    let generics = dispatch.generics(item_impl);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let turbofish = ty_generics.as_turbofish();
+    let trait_ident = &item_impl
+        .trait_
+        .as_ref()
+        .unwrap()
+        .1
+        .segments
+        .last()
+        .unwrap()
+        .ident;
+    let title = trait_ident.to_string();
+    let doc = format!(
+        "The OpenAPI 3.1 document describing the REST endpoints of `{}`.",
+        trait_ident
+    );
+
+    quote! {
+        #[doc = #doc]
+        pub fn openapi #impl_generics () -> serde_json::Value #where_clause {
+            let mut spec = multi_rpc::openapi::OpenApi::new();
+            #(#operations)*
+            spec.into_document(#title, env!("CARGO_PKG_VERSION"))
+        }
+
+        pub fn rest_axum_with_openapi #impl_generics (addr: std::net::SocketAddr)
+            -> impl FnOnce(#handle_ty) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>
+        #where_clause
+        {
+            move |service| {
+                let document = openapi #turbofish ();
+                let app = rest_axum_router #turbofish ()
+                    .route(
+                        "/openapi.json",
+                        axum::routing::get(move || async move { axum::Json(document) }),
+                    )
+                    .with_state(service);
+                Box::pin(serve_rest_axum(addr, app))
+            }
+        }
+    }
+}
+
+// Generates the statements that add the OpenAPI operation for the route of `method`.
+#[cfg(feature = "openapi")]
+fn openapi_operation(method: &ImplItemFn, rest_attr: &RestAttribute) -> Result<TokenStream> {
+    let method_name = method.sig.ident.to_string();
+    let http_method = rest_attr.method.to_string().to_lowercase();
+    // OpenAPI has no wildcards, `{*path}` is described as a plain parameter.
+    let path = rest_attr.path.value().replace("{*", "{");
+
+    let arg_tys: HashMap<_, _> = method
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pt) => match &*pt.pat {
+                Pat::Ident(pi) => Some((pi.ident.clone(), &*pt.ty)),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+
+    // Schemas are computed up front, as types with commas can't be written inside `json!`.
+    let mut schemas = Vec::new();
+    let mut schema_for = |ty: &Type| {
+        let ident = format_ident!("schema_{}", schemas.len());
+        schemas.push(quote! {
+            let #ident = (&multi_rpc::schema::SchemaMapping::<#ty>::new()).schema(spec.generator());
+        });
+        ident
+    };
+
+    let mut parameters = Vec::new();
+    for param in rest_attr.path_params()? {
+        let name = param.to_string();
+        let schema = schema_for(arg_tys[&param]);
+        parameters.push(quote! {
+            { "name": #name, "in": "path", "required": true, "schema": #schema }
+        });
+    }
+    for param in &rest_attr.query_params {
+        let name = param.public_name.to_string();
+        let ty = arg_tys[&param.private_name];
+        let required = !is_option(ty);
+        let schema = schema_for(ty);
+        parameters.push(quote! {
+            { "name": #name, "in": "query", "required": #required, "schema": #schema }
+        });
+    }
+
+    for param in &rest_attr.header_params {
+        let name = param.header_name();
+        let ty = arg_tys[&param.private_name];
+        let required = !is_option(ty);
+        let schema = schema_for(ty);
+        parameters.push(quote! {
+            { "name": #name, "in": "header", "required": #required, "schema": #schema }
+        });
+    }
+    for param in &rest_attr.cookie_params {
+        let name = param.cookie_name();
+        let ty = arg_tys[&param.private_name];
+        let required = !is_option(ty);
+        let schema = schema_for(ty);
+        parameters.push(quote! {
+            { "name": #name, "in": "cookie", "required": #required, "schema": #schema }
+        });
+    }
+
+    let mut request_body = None;
+    for (params, content_type) in [
+        (&rest_attr.body_params, "application/json"),
+        (&rest_attr.form_params, "application/x-www-form-urlencoded"),
+        (&rest_attr.multipart_params, "multipart/form-data"),
+    ] {
+        if params.is_empty() {
+            continue;
+        }
+        let mut properties = Vec::new();
+        let mut required = Vec::new();
+        for param in params {
+            let name = param.public_name.to_string();
+            let ty = arg_tys[&param.private_name];
+            if !is_option(ty) {
+                required.push(name.clone());
             }
+            if content_type == "multipart/form-data" && is_bytes(option_inner_ty(ty).unwrap_or(ty))
+            {
+                // A file, whose contents are the bytes of the argument.
+                properties.push(quote! {
+                    #name: { "type": "string", "contentMediaType": "application/octet-stream" }
+                });
+                continue;
+            }
+            let schema = schema_for(ty);
+            properties.push(quote! { #name: #schema });
         }
+        // Without a required field, the server also accepts a request without a body.
+        let body_required = !required.is_empty();
+        request_body = Some(quote! {
+            {
+                "required": #body_required,
+                "content": {
+                    #content_type: {
+                        "schema": {
+                            "type": "object",
+                            "properties": { #(#properties),* },
+                            "required": [#(#required),*],
+                        }
+                    }
+                }
+            }
+        });
     }
+
+    // Rejected input and errors returned by the method are described as problem details, with
+    // a status depending on the error, see `multi_rpc::error::ProtocolError`.
+    let problem = schema_for(&syn::parse_quote! { multi_rpc::problem::Problem });
+    let error = quote! {
+        "default": {
+            "description": "An error, described as RFC 7807 problem details.",
+            "content": { "application/problem+json": { "schema": #problem } }
+        }
+    };
+
+    let success = match &rest_attr.status {
+        Some(status) => status.base10_digits().to_string(),
+        None => "200".to_string(),
+    };
+    let responses = if let Some(item_ty) = streaming_item(&method.sig) {
+        let schema = schema_for(item_ty);
+        quote! {
+            {
+                #success: {
+                    "description": "A stream of Server-Sent Events, each carrying one JSON item.",
+                    "content": { "text/event-stream": { "schema": #schema } }
+                },
+                #error
+            }
+        }
+    } else {
+        let ok_ty = match &method.sig.output {
+            ReturnType::Default => syn::parse_quote! { () },
+            ReturnType::Type(_, ty) => match result_ok_ty(ty) {
+                Some(ok_ty) => ok_ty.clone(),
+                None => (**ty).clone(),
+            },
+        };
+        // Raw text bodies are strings, and other raw bodies are binary.
+        let (media_type, schema) = match rest_attr.raw_body() {
+            Some(produces) if produces.value().starts_with("text/") => {
+                (produces.value(), quote! { { "type": "string" } })
+            }
+            Some(produces) => {
+                let media_type = produces.value();
+                let schema = quote! { { "type": "string", "contentMediaType": #media_type } };
+                (media_type, schema)
+            }
+            None => {
+                let media_type = match &rest_attr.produces {
+                    Some(produces) => produces.value(),
+                    None => "application/json".to_string(),
+                };
+                let schema = schema_for(&ok_ty);
+                (media_type, quote! { #schema })
+            }
+        };
+        quote! {
+            {
+                #success: {
+                    "description": "Success.",
+                    "content": { #media_type: { "schema": #schema } }
+                },
+                #error
+            }
+        }
+    };
+
+    let description = doc_string(&method.attrs);
+    let description = description.map(|description| quote! { "description": #description, });
+    let request_body = request_body.map(|body| quote! { "requestBody": #body, });
+
+    Ok(quote! {
+        {
+            use multi_rpc::schema::MapJsonSchema as _;
+            use multi_rpc::schema::MapOtherSchema as _;
+            #(#schemas)*
+            let operation = serde_json::json!({
+                "operationId": #method_name,
+                #description
+                "parameters": [#(#parameters),*],
+                #request_body
+                "responses": #responses,
+            });
+            spec.add_operation(#http_method, #path, operation);
+        }
+    })
 }
@@ -3,34 +3,118 @@ use quote::format_ident;
 use quote::quote;
 use syn::punctuated::Punctuated;
 use syn::FnArg;
+use syn::Ident;
 use syn::ImplItem;
 use syn::ItemImpl;
 use syn::ItemTrait;
-use syn::Pat;
+use syn::ReturnType;
+use syn::Signature;
 use syn::Token;
 use syn::TraitItem;
 
+use super::call_args;
+use super::result_ok_ty;
+use super::streaming_item;
+use super::takes_context;
+use super::wire_inputs;
 use super::Protocol;
+use crate::dispatch::Dispatch;
 pub struct Tarpc;
 
 impl Protocol for Tarpc {
-    fn transform_trait(&self, item_trait: &ItemTrait) -> TokenStream {
+    fn name(&self) -> &'static str {
+        "tarpc"
+    }
+
+    fn transform_trait(&self, item_trait: &ItemTrait) -> syn::Result<TokenStream> {
+        let sigs: Vec<&Signature> = item_trait
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                TraitItem::Fn(method) => Some(&method.sig),
+                _ => None,
+            })
+            .collect();
+        validate_session_names(&sigs)?;
+
         let original_trait_ident = &item_trait.ident;
         let tarpc_trait_ident = format_ident!("{}Tarpc", original_trait_ident);
         let generated_client_ident = format_ident!("{}Client", tarpc_trait_ident);
         let desired_client_ident = format_ident!("{}Client", original_trait_ident);
 
-        let methods = item_trait.items.iter().filter_map(|item| {
-            if let TraitItem::Fn(method) = item {
-                let mut sig = method.sig.clone();
-                sig.inputs = sig.inputs.into_iter().skip(1).collect();
-                Some(quote! { #sig; })
-            } else {
-                None
+        let methods = sigs.iter().map(|method_sig| {
+            let mut sig = (*method_sig).clone();
+            sig.inputs = wire_inputs(method_sig).cloned().collect();
+            // tarpc services are always async; sync methods are adapted in the impl.
+            sig.asyncness = Some(Default::default());
+            let Some(item_ty) = streaming_item(method_sig) else {
+                return quote! { #sig; };
+            };
+
+            let [open, next, cancel] = session_method_idents(&sig.ident);
+            sig.ident = open;
+            sig.output = syn::parse_quote! { -> multi_rpc::tarpc_streaming::SessionId };
+            quote! {
+                #sig;
+                async fn #next(session: multi_rpc::tarpc_streaming::SessionId) -> multi_rpc::tarpc_streaming::Chunk<#item_ty>;
+                async fn #cancel(session: multi_rpc::tarpc_streaming::SessionId);
             }
         });
 
-        quote! {
+        // Streaming methods are opened as sessions, which the client reads as a stream.
+        let stream_methods = sigs.iter().filter_map(|sig| {
+            let item_ty = streaming_item(sig)?;
+            let method_ident = &sig.ident;
+            let [open, next, cancel] = session_method_idents(method_ident);
+            let (args, arg_tys): (Vec<_>, Vec<_>) = wire_inputs(sig)
+                .filter_map(|arg| match arg {
+                    FnArg::Typed(pt) => Some(&pt.ty),
+                    FnArg::Receiver(_) => None,
+                })
+                .enumerate()
+                .map(|(i, ty)| (format_ident!("arg{}", i), ty))
+                .unzip();
+            let doc = format!(
+                "Calls `{method_ident}` and receives its items as they are produced. Dropping the \
+                 stream before its end cancels it on the server."
+            );
+            Some(quote! {
+                #[doc = #doc]
+                pub async fn #method_ident(
+                    &self,
+                    ctx: tarpc::context::Context,
+                    #(#args: #arg_tys),*
+                ) -> Result<
+                    multi_rpc::Streaming<Result<#item_ty, tarpc::client::RpcError>>,
+                    tarpc::client::RpcError,
+                > {
+                    let session = self.#open(ctx, #(#args),*).await?;
+                    let client = self.clone();
+                    let next = move || {
+                        let client = client.clone();
+                        async move { client.#next(tarpc::context::current(), session).await }
+                    };
+                    let client = self.clone();
+                    let cancel = move || {
+                        tokio::spawn(async move {
+                            let _ = client.#cancel(tarpc::context::current(), session).await;
+                        });
+                    };
+                    Ok(multi_rpc::tarpc_streaming::receive(next, cancel))
+                }
+            })
+        });
+
+        let connect_arms = codecs().into_iter().map(|(variant, format)| {
+            quote! {
+                multi_rpc::codec::TarpcCodec::#variant => {
+                    let transport = tarpc::serde_transport::tcp::connect(addr, #format::default).await?;
+                    #desired_client_ident::new(config, transport).spawn()
+                }
+            }
+        });
+
+        Ok(quote! {
             #[tarpc::service]
             pub trait #tarpc_trait_ident { #(#methods)* }
 
@@ -38,16 +122,113 @@ impl Protocol for Tarpc {
             // This makes the change non-breaking for existing clients.
             pub use self::#generated_client_ident as #desired_client_ident;
 
-            #[derive(Clone)]
-            pub struct TarpcAdapter<S>(
-                // An Arc reference to the Mutex in ServerBuilder
-                pub std::sync::Arc<tokio::sync::Mutex<S>>
+            impl #generated_client_ident {
+                #(#stream_methods)*
+            }
+
+            /// Connects a tarpc client to a server started by `tarpc_tcp_with_codec` with the same `codec`.
+            pub async fn connect_tarpc(
+                addr: impl tokio::net::ToSocketAddrs,
+                codec: multi_rpc::codec::TarpcCodec,
+            ) -> std::io::Result<#desired_client_ident> {
+                let config = tarpc::client::Config::default();
+                let client = match codec {
+                    #(#connect_arms)*
+                };
+                Ok(client)
+            }
+
+            pub struct TarpcAdapter<H>(
+                // The service handle from ServerBuilder: an `Arc<RwLock<S>>` shared with the
+                // other servers, or an `ActorHandle` in actor mode.
+                pub H,
+                // The address of the connected client, for the `RequestContext`.
+                pub Option<std::net::SocketAddr>,
+                // The streams opened by the client's calls to streaming methods.
+                pub multi_rpc::tarpc_streaming::Sessions,
             );
-        }
+
+            // Implemented by hand so that cloning the adapter only clones the handle, never the service.
+            impl<H: Clone> Clone for TarpcAdapter<H> {
+                fn clone(&self) -> Self {
+                    Self(self.0.clone(), self.1, self.2.clone())
+                }
+            }
+        })
+    }
+
+    fn transform_remote(&self, item_trait: &ItemTrait) -> syn::Result<TokenStream> {
+        let client_ident = format_ident!("{}TarpcClient", item_trait.ident);
+
+        let arms = item_trait.items.iter().filter_map(|item| {
+            let TraitItem::Fn(method) = item else {
+                return None;
+            };
+            // `remote` rejects streaming methods.
+            if streaming_item(&method.sig).is_some() {
+                return None;
+            }
+            let method_ident = &method.sig.ident;
+            let method_name = method_ident.to_string();
+            let (args, arg_tys): (Vec<_>, Vec<_>) = wire_inputs(&method.sig)
+                .filter_map(|arg| match arg {
+                    FnArg::Typed(pt) => Some(&pt.ty),
+                    FnArg::Receiver(_) => None,
+                })
+                .enumerate()
+                .map(|(i, ty)| (format_ident!("arg{}", i), ty))
+                .unzip();
+
+            let is_result =
+                matches!(&method.sig.output, ReturnType::Type(_, ty) if result_ok_ty(ty).is_some());
+            let encode = if is_result {
+                quote! {
+                    match response {
+                        Ok(value) => multi_rpc::client::encode(&value),
+                        Err(err) => Err(multi_rpc::client::ClientError::Service(multi_rpc::client::encode(&err)?)),
+                    }
+                }
+            } else {
+                quote! { multi_rpc::client::encode(&response) }
+            };
+
+            Some(quote! {
+                #method_name => {
+                    let mut params = params.into_iter();
+                    #(let #args: #arg_tys = multi_rpc::client::param(&mut params)?;)*
+                    let response = self
+                        .#method_ident(tarpc::context::current(), #(#args),*)
+                        .await
+                        .map_err(|err| multi_rpc::client::ClientError::Transport(err.to_string()))?;
+                    #encode
+                }
+            })
+        });
+
+        Ok(quote! {
+            impl multi_rpc::client::Transport for #client_ident {
+                fn call<'a>(
+                    &'a self,
+                    method: &'a str,
+                    params: Vec<serde_json::Value>,
+                ) -> futures::future::BoxFuture<'a, Result<serde_json::Value, multi_rpc::client::ClientError>> {
+                    Box::pin(async move {
+                        match method {
+                            #(#arms)*
+                            _ => Err(multi_rpc::client::ClientError::UnsupportedMethod(method.to_string())),
+                        }
+                    })
+                }
+            }
+        })
     }
 
-    fn transform_impl(&self, item_impl: &ItemImpl) -> TokenStream {
-        let self_ty = &item_impl.self_ty;
+    fn transform_impl(
+        &self,
+        item_impl: &ItemImpl,
+        dispatch: &Dispatch,
+    ) -> syn::Result<TokenStream> {
+        let handle_ty = dispatch.handle_ty(item_impl);
         let trait_ident = &item_impl
             .trait_
             .as_ref()
@@ -62,59 +243,216 @@ impl Protocol for Tarpc {
         let request_ident = format_ident!("{}Request", tarpc_trait_ident);
         let response_ident = format_ident!("{}Response", tarpc_trait_ident);
 
+        let generics = dispatch.generics(item_impl);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let turbofish = ty_generics.as_turbofish();
+
+        // `run_tarpc_server` is additionally generic over the listener and its transports, which
+        // it receives along with the client's address. The parameters are prefixed so that they
+        // can't collide with the generic parameters of the `impl` block.
+        let mut server_generics = generics.clone();
+        server_generics
+            .params
+            .push(syn::parse_quote! { __MultiRpcListener });
+        server_generics
+            .params
+            .push(syn::parse_quote! { __MultiRpcTransport });
+        let server_predicates = &mut server_generics.make_where_clause().predicates;
+        server_predicates.push(syn::parse_quote! {
+            __MultiRpcListener: futures::Stream<
+                Item = std::io::Result<(Option<std::net::SocketAddr>, __MultiRpcTransport)>
+            > + Unpin
+        });
+        server_predicates.push(syn::parse_quote! {
+            __MultiRpcTransport: tarpc::Transport<
+                tarpc::Response<#response_ident>,
+                tarpc::ClientMessage<#request_ident>
+            > + Send + 'static
+        });
+        let (server_impl_generics, _, server_where_clause) = server_generics.split_for_impl();
+
         let adapter_methods = item_impl.items.iter().filter_map(|item| {
             if let ImplItem::Fn(method) = item {
                 let sig = &method.sig;
                 let method_name = &sig.ident;
                 let return_ty = &sig.output;
-                let user_args_and_tys: Punctuated<_, Token![,]> = sig.inputs.iter().skip(1).cloned().collect();
-                let original_arg_names: Vec<Pat> = user_args_and_tys.iter().filter_map(|arg| if let FnArg::Typed(pt) = arg { Some((*pt.pat).clone()) } else { None }).collect();
+                let user_args_and_tys: Punctuated<_, Token![,]> = wire_inputs(sig).cloned().collect();
+                let (context_arg, context) = if takes_context(sig) {
+                    (
+                        quote! { context },
+                        quote! {
+                            let request_context = multi_rpc::context::RequestContext::from_tarpc(&context, self.1);
+                        },
+                    )
+                } else {
+                    (quote! { _ }, quote! {})
+                };
+                let args = call_args(sig, &quote! { request_context });
 
-                let method_call = quote! { self.0.lock().await.#method_name(#(#original_arg_names),*).await };
+                // tarpc has no error responses, so a call the actor can't answer fails like a
+                // panicking method, which closes the connection.
+                let on_actor_error = quote! { panic!("{}", err) };
+                let method_call = dispatch.call(&quote! { self.0 }, method, &args, &on_actor_error);
 
+                let Some(item_ty) = streaming_item(sig) else {
+                    return Some(quote! {
+                        async fn #method_name(self, #context_arg: tarpc::context::Context, #user_args_and_tys) #return_ty {
+                            #context
+                            #method_call
+                        }
+                    });
+                };
+                let [open, next, cancel] = session_method_idents(method_name);
                 Some(quote! {
-                    async fn #method_name(self, _: tarpc::context::Context, #user_args_and_tys) #return_ty {
-                        #method_call
+                    async fn #open(self, #context_arg: tarpc::context::Context, #user_args_and_tys) -> multi_rpc::tarpc_streaming::SessionId {
+                        #context
+                        let stream = #method_call;
+                        self.2.open(stream)
+                    }
+
+                    async fn #next(
+                        self,
+                        context: tarpc::context::Context,
+                        session: multi_rpc::tarpc_streaming::SessionId,
+                    ) -> multi_rpc::tarpc_streaming::Chunk<#item_ty> {
+                        self.2.next(session, context.deadline).await
+                    }
+
+                    async fn #cancel(self, _: tarpc::context::Context, session: multi_rpc::tarpc_streaming::SessionId) {
+                        self.2.cancel(session)
                     }
                 })
             } else { None }
         });
 
-        quote! {
-            impl #tarpc_trait_ident for TarpcAdapter<#self_ty> {
+        let listen_arms = codecs().into_iter().map(|(variant, format)| {
+            quote! {
+                multi_rpc::codec::TarpcCodec::#variant => {
+                    let listener = tarpc::serde_transport::tcp::listen(addr, #format::default).await?;
+                    let listener = futures::StreamExt::map(listener, |transport| {
+                        transport.map(|transport| (transport.peer_addr().ok(), transport))
+                    });
+                    run_tarpc_server(service, listener).await
+                }
+            }
+        });
+
+        Ok(quote! {
+            impl #impl_generics #tarpc_trait_ident for TarpcAdapter<#handle_ty> #where_clause {
                 #(#adapter_methods)*
             }
 
-            async fn run_tarpc_server<L, T>(service: std::sync::Arc<tokio::sync::Mutex<#self_ty>>, mut listener: L)
-            where
-                L: futures::Stream<Item = std::io::Result<T>> + Unpin,
-                T: tarpc::Transport<
-                    tarpc::Response<#response_ident>,
-                    tarpc::ClientMessage<#request_ident>
-                > + Send + 'static,
+            async fn run_tarpc_server #server_impl_generics (service: #handle_ty, mut listener: __MultiRpcListener)
+                -> std::io::Result<()>
+            #server_where_clause
             {
                 use futures::StreamExt;
                 use tarpc::server::{BaseChannel, Channel};
 
                 println!("📡 Tarpc server starting...");
-                while let Some(Ok(transport)) = listener.next().await {
-                    let server = TarpcAdapter(service.clone());
+                while let Some(accepted) = listener.next().await {
+                    let (peer_addr, transport) = match accepted {
+                        Ok(accepted) => accepted,
+                        Err(err) => {
+                            multi_rpc::accept::back_off("Tarpc", &err).await;
+                            continue;
+                        }
+                    };
+                    let server = TarpcAdapter(service.clone(), peer_addr, Default::default());
                     let channel = BaseChannel::with_defaults(transport).execute(server.serve());
                     tokio::spawn(channel.for_each_concurrent(None, |f| f));
                 }
+                Ok(())
             }
 
-            pub fn tarpc_tcp(addr: std::net::SocketAddr)
-                -> impl FnOnce(std::sync::Arc<tokio::sync::Mutex<#self_ty>>) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>
+            // Serves `service` on `addr` until the listener ends, or fails to bind `addr`.
+            async fn serve_tarpc #impl_generics (
+                addr: std::net::SocketAddr,
+                codec: multi_rpc::codec::TarpcCodec,
+                service: #handle_ty,
+            ) -> std::io::Result<()>
+            #where_clause
+            {
+                // Each format yields a different transport type, so every arm runs its own server.
+                match codec {
+                    #(#listen_arms)*
+                }
+            }
+
+            pub fn tarpc_tcp #impl_generics (addr: std::net::SocketAddr)
+                -> impl FnOnce(#handle_ty) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>
+            #where_clause
+            {
+                tarpc_tcp_with_codec #turbofish (addr, multi_rpc::codec::TarpcCodec::Json)
+            }
+
+            pub fn tarpc_tcp_with_codec #impl_generics (
+                addr: std::net::SocketAddr,
+                codec: multi_rpc::codec::TarpcCodec,
+            ) -> impl FnOnce(#handle_ty) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>
+            #where_clause
             {
                 move |service|
                 {
                     Box::pin(async move {
-                        let listener = tarpc::serde_transport::tcp::listen(addr, tarpc::tokio_serde::formats::Json::default).await.unwrap();
-                        run_tarpc_server(service, listener).await;
+                        if let Err(err) = serve_tarpc #turbofish (addr, codec, service).await {
+                            eprintln!("Tarpc server failed to listen on {}: {}", addr, err);
+                        }
                     })
                 }
             }
+        })
+    }
+}
+
+// The variants of `multi_rpc::codec::TarpcCodec` enabled for this build, with their format in
+// `tarpc::tokio_serde::formats`.
+fn codecs() -> Vec<(TokenStream, TokenStream)> {
+    let mut codecs = vec![
+        (
+            quote! { Json },
+            quote! { tarpc::tokio_serde::formats::Json },
+        ),
+        (
+            quote! { Bincode },
+            quote! { tarpc::tokio_serde::formats::Bincode },
+        ),
+    ];
+    if cfg!(feature = "tarpc-messagepack") {
+        codecs.push((
+            quote! { MessagePack },
+            quote! { tarpc::tokio_serde::formats::MessagePack },
+        ));
+    }
+    if cfg!(feature = "tarpc-cbor") {
+        codecs.push((
+            quote! { Cbor },
+            quote! { tarpc::tokio_serde::formats::Cbor },
+        ));
+    }
+    codecs
+}
+
+// The tarpc methods serving the sessions of a streaming method, e.g. `open_ticks`, `next_ticks`
+// and `cancel_ticks` for `ticks`.
+fn session_method_idents(method_ident: &Ident) -> [Ident; 3] {
+    ["open", "next", "cancel"].map(|prefix| format_ident!("{}_{}", prefix, method_ident))
+}
+
+// The session methods of a streaming method share the tarpc service with the other methods.
+fn validate_session_names(sigs: &[&Signature]) -> syn::Result<()> {
+    for sig in sigs.iter().filter(|sig| streaming_item(sig).is_some()) {
+        for session_ident in session_method_idents(&sig.ident) {
+            if sigs.iter().any(|other| other.ident == session_ident) {
+                return Err(syn::Error::new_spanned(
+                    &sig.ident,
+                    format!(
+                        "tarpc serves `{}` with a `{}` method, which collides with the method of that name",
+                        sig.ident, session_ident
+                    ),
+                ));
+            }
         }
     }
+    Ok(())
 }
@@ -0,0 +1,146 @@
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use quote::quote_spanned;
+use syn::spanned::Spanned;
+use syn::FnArg;
+use syn::ItemTrait;
+use syn::Pat;
+use syn::ReturnType;
+use syn::TraitItem;
+
+use crate::protocols::is_context_arg;
+use crate::protocols::result_ok_ty;
+use crate::protocols::streaming_item;
+
+/// Generates `{Trait}Remote` for `#[multi_rpc_trait(remote)]`, which implements the user's trait
+/// by calling a remote server through a `multi_rpc::client::Transport`.
+///
+/// Every method must return `Result<T, E>` with `E: From<multi_rpc::client::ClientError>`, and
+/// failed calls are converted into `E` by `multi_rpc::client::FailureMapping`: errors the service
+/// returned are decoded if they were received as is, and other failures convert through
+/// `From<multi_rpc::client::ClientError>`.
+pub fn generate(item_trait: &ItemTrait) -> syn::Result<TokenStream> {
+    let trait_ident = &item_trait.ident;
+    let remote_ident = format_ident!("{}Remote", trait_ident);
+
+    let mut methods = Vec::new();
+    let mut errors: Option<syn::Error> = None;
+    let mut push_error = |err: syn::Error| match &mut errors {
+        Some(errors) => errors.combine(err),
+        None => errors = Some(err),
+    };
+
+    for item in &item_trait.items {
+        let TraitItem::Fn(method) = item else {
+            continue;
+        };
+        let sig = &method.sig;
+        let method_name = sig.ident.to_string();
+
+        if sig.asyncness.is_none() {
+            push_error(syn::Error::new_spanned(
+                sig.fn_token,
+                "`remote` can't implement sync methods, as every call goes over the network",
+            ));
+            continue;
+        }
+        if streaming_item(sig).is_some() {
+            push_error(syn::Error::new_spanned(
+                &sig.output,
+                "`remote` doesn't support streaming methods",
+            ));
+            continue;
+        }
+        // A failed call must be returned to the caller, so only a `Result` can be implemented.
+        let not_result = || {
+            syn::Error::new_spanned(
+                &sig.output,
+                "remote methods must return `Result<_, E>` with `E: From<ClientError>`",
+            )
+        };
+        let (return_ty, ok_ty) = match &sig.output {
+            ReturnType::Type(_, ty) => match result_ok_ty(ty) {
+                Some(ok_ty) => (&**ty, ok_ty),
+                None => {
+                    push_error(not_result());
+                    continue;
+                }
+            },
+            ReturnType::Default => {
+                // `sig.output` has no tokens, so the error points at the method name.
+                push_error(syn::Error::new_spanned(
+                    &sig.ident,
+                    not_result().to_string(),
+                ));
+                continue;
+            }
+        };
+
+        // The `RequestContext` describes requests to this service, so it isn't forwarded.
+        let mut args = Vec::new();
+        let mut unused = Vec::new();
+        for arg in &sig.inputs {
+            if let FnArg::Typed(pt) = arg {
+                match &*pt.pat {
+                    Pat::Ident(pi) if is_context_arg(arg) => unused.push(&pi.ident),
+                    _ if is_context_arg(arg) => {}
+                    Pat::Ident(pi) => args.push(&pi.ident),
+                    pat => push_error(syn::Error::new_spanned(
+                        pat,
+                        "arguments must be plain identifiers with `remote`",
+                    )),
+                }
+            }
+        }
+
+        // `FailureMapping` requires `E: From<ClientError>`, reported at the return type.
+        let failure = quote_spanned! {return_ty.span()=>
+            (&&multi_rpc::client::FailureMapping::of_result(
+                std::marker::PhantomData::<#return_ty>,
+            ))
+            .failure(err)
+        };
+
+        methods.push(quote! {
+            #sig {
+                #[allow(unused_imports)]
+                use multi_rpc::client::{ConvertFailure as _, DecodeOrConvertFailure as _};
+                #(let _ = #unused;)*
+                let response: Result<#ok_ty, multi_rpc::client::ClientError> = async {
+                    let params = vec![#(multi_rpc::client::encode(&#args)?),*];
+                    let value = self.0.call(#method_name, params).await?;
+                    multi_rpc::client::decode(value)
+                }
+                .await;
+                response.map_err(|err| #failure)
+            }
+        });
+    }
+
+    if let Some(err) = errors {
+        return Err(err);
+    }
+
+    let doc = format!(
+        "A `{}` that calls a remote server through a `multi_rpc::client::Transport`.",
+        trait_ident
+    );
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Clone)]
+        pub struct #remote_ident(std::sync::Arc<dyn multi_rpc::client::Transport>);
+
+        impl #remote_ident {
+            /// Creates a remote service that sends its calls through `transport`.
+            pub fn new(transport: impl multi_rpc::client::Transport + 'static) -> Self {
+                Self(std::sync::Arc::new(transport))
+            }
+        }
+
+        impl super::#trait_ident for #remote_ident {
+            #(#methods)*
+        }
+    })
+}
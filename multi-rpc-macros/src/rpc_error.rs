@@ -0,0 +1,264 @@
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::Attribute;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Fields;
+use syn::Token;
+
+// The `#[rpc(...)]` attribute of an error enum variant, or of an error struct.
+//
+// `status = 404` and `code = -32004` are the HTTP status and JSON-RPC error code, defaulting to
+// `500` and `-32603`. `data` sends the fields as the structured data of the error.
+struct RpcAttribute {
+    status: Option<syn::LitInt>,
+    code: Option<syn::Expr>,
+    data: bool,
+}
+
+impl RpcAttribute {
+    fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut rpc_attr = RpcAttribute {
+            status: None,
+            code: None,
+            data: false,
+        };
+        let Some(attr) = attrs.iter().find(|a| a.path().is_ident("rpc")) else {
+            return Ok(rpc_attr);
+        };
+
+        let metas = attr.parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)?;
+        for meta in metas {
+            let key = meta.path();
+            if key.is_ident("status") {
+                let nv = meta.require_name_value()?;
+                let status = match &nv.value {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Int(status),
+                        ..
+                    }) if matches!(status.base10_parse::<u16>(), Ok(100..=599)) => status.clone(),
+                    value => {
+                        return Err(syn::Error::new_spanned(
+                            value,
+                            "expected an HTTP status code such as `404`",
+                        ))
+                    }
+                };
+                set_once(&mut rpc_attr.status, status, key)?;
+            } else if key.is_ident("code") {
+                let nv = meta.require_name_value()?;
+                if !is_i32_literal(&nv.value) {
+                    return Err(syn::Error::new_spanned(
+                        &nv.value,
+                        "expected a JSON-RPC error code such as `-32004`",
+                    ));
+                }
+                set_once(&mut rpc_attr.code, nv.value.clone(), key)?;
+            } else if key.is_ident("data") {
+                meta.require_path_only()?;
+                if rpc_attr.data {
+                    return Err(syn::Error::new_spanned(
+                        key,
+                        "argument may only be specified once",
+                    ));
+                }
+                rpc_attr.data = true;
+            } else {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    "unknown `#[rpc]` argument, expected one of `status`, `code`, `data`",
+                ));
+            }
+        }
+        Ok(rpc_attr)
+    }
+}
+
+// An integer literal that fits an `i32`, possibly negated.
+fn is_i32_literal(expr: &syn::Expr) -> bool {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(int),
+            ..
+        }) => int.base10_parse::<i32>().is_ok(),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => match &**expr {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(int),
+                ..
+            }) => format!("-{}", int.base10_digits()).parse::<i32>().is_ok(),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn set_once<T>(slot: &mut Option<T>, value: T, key: &syn::Path) -> syn::Result<()> {
+    if slot.is_some() {
+        return Err(syn::Error::new_spanned(
+            key,
+            "argument may only be specified once",
+        ));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+// A match arm of `http_status`, `jsonrpc_code` and `data` for a variant, or the single arm of a
+// struct.
+struct Arm {
+    // The variant or struct, describing its code in the OpenRPC document.
+    name: String,
+    pattern: TokenStream,
+    status: TokenStream,
+    code: TokenStream,
+    data: TokenStream,
+}
+
+fn arm(name: String, path: TokenStream, fields: &Fields, rpc_attr: RpcAttribute) -> Arm {
+    let status = match &rpc_attr.status {
+        Some(status) => quote! { #status },
+        None => quote! { 500 },
+    };
+    let code = match &rpc_attr.code {
+        Some(code) => quote! { #code },
+        None => quote! { -32603 },
+    };
+
+    let (pattern, data) = match fields {
+        // Without `data`, the fields aren't used and are ignored by the pattern.
+        Fields::Unit => (path, quote! { None }),
+        Fields::Named(_) if !rpc_attr.data => (quote! { #path { .. } }, quote! { None }),
+        Fields::Unnamed(_) if !rpc_attr.data => (quote! { #path(..) }, quote! { None }),
+        Fields::Named(named) => {
+            let idents: Vec<_> = named
+                .named
+                .iter()
+                .map(|f| f.ident.as_ref().unwrap())
+                .collect();
+            let names = idents.iter().map(|ident| ident.to_string());
+            let data = quote! {{
+                let mut data = serde_json::Map::new();
+                #(data.insert(#names.to_string(), serde_json::to_value(#idents).ok()?);)*
+                Some(serde_json::Value::Object(data))
+            }};
+            (quote! { #path { #(#idents),* } }, data)
+        }
+        Fields::Unnamed(unnamed) => {
+            let idents: Vec<_> = (0..unnamed.unnamed.len())
+                .map(|i| format_ident!("field{}", i))
+                .collect();
+            let data = if idents.len() == 1 {
+                quote! { serde_json::to_value(field0).ok() }
+            } else {
+                quote! {
+                    Some(serde_json::Value::Array(vec![
+                        #(serde_json::to_value(#idents).ok()?),*
+                    ]))
+                }
+            };
+            (quote! { #path(#(#idents),*) }, data)
+        }
+    };
+
+    Arm {
+        name,
+        pattern,
+        status,
+        code,
+        data,
+    }
+}
+
+/// Generates the `multi_rpc::error::ProtocolError` impl for `#[derive(RpcError)]`.
+///
+/// Every variant of an enum, or the struct itself, chooses its HTTP status and JSON-RPC error code
+/// with `#[rpc(status = 404, code = -32004)]`. With `data`, its fields are serialized as the
+/// structured data of the error: a single unnamed field as is, several as an array, and named
+/// fields as an object. The codes are listed, by variant name, as the errors of the OpenRPC
+/// document.
+pub fn derive(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let arms = match &input.data {
+        Data::Enum(data_enum) => {
+            if let Some(attr) = input.attrs.iter().find(|a| a.path().is_ident("rpc")) {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "`#[rpc]` goes on the variants of an enum",
+                ));
+            }
+            let mut arms = Vec::new();
+            for variant in &data_enum.variants {
+                let variant_ident = &variant.ident;
+                let rpc_attr = RpcAttribute::from_attrs(&variant.attrs)?;
+                arms.push(arm(
+                    variant_ident.to_string(),
+                    quote! { Self::#variant_ident },
+                    &variant.fields,
+                    rpc_attr,
+                ));
+            }
+            arms
+        }
+        Data::Struct(data_struct) => {
+            let rpc_attr = RpcAttribute::from_attrs(&input.attrs)?;
+            vec![arm(
+                ident.to_string(),
+                quote! { Self },
+                &data_struct.fields,
+                rpc_attr,
+            )]
+        }
+        Data::Union(data_union) => {
+            return Err(syn::Error::new_spanned(
+                data_union.union_token,
+                "`RpcError` can only be derived for enums and structs",
+            ))
+        }
+    };
+
+    let patterns: Vec<_> = arms.iter().map(|arm| &arm.pattern).collect();
+    let statuses = arms.iter().map(|arm| &arm.status);
+    let codes = arms.iter().map(|arm| &arm.code);
+    let data = arms.iter().map(|arm| &arm.data);
+    let errors = arms.iter().map(|arm| {
+        let code = &arm.code;
+        let name = &arm.name;
+        quote! { (#code, #name) }
+    });
+
+    Ok(quote! {
+        impl #impl_generics multi_rpc::error::ProtocolError for #ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn http_status(&self) -> u16 {
+                match self {
+                    #(#patterns => #statuses,)*
+                }
+            }
+
+            #[allow(unused_variables)]
+            fn jsonrpc_code(&self) -> i32 {
+                match self {
+                    #(#patterns => #codes,)*
+                }
+            }
+
+            fn data(&self) -> Option<serde_json::Value> {
+                match self {
+                    #(#patterns => #data,)*
+                }
+            }
+
+            fn jsonrpc_errors() -> Vec<(i32, &'static str)> {
+                vec![#(#errors),*]
+            }
+        }
+    })
+}
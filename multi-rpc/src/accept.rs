@@ -0,0 +1,33 @@
+//! Errors of accepting connections, for the servers that accept their own connections.
+//!
+//! The JSON-RPC server and the generated tarpc servers keep accepting connections after an
+//! error. Errors that only concern the connection being accepted are skipped, and any other
+//! error, e.g. the process running out of file descriptors, is reported and retried after a
+//! delay, as retrying at once won't fix it.
+
+use std::io;
+use std::io::ErrorKind;
+use std::time::Duration;
+
+// How long a server waits before accepting connections again after an error that doesn't concern
+// a single connection.
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_secs(1);
+
+/// Handles an error of accepting a connection of the `server`, after which the server accepts
+/// connections again.
+pub async fn back_off(server: &str, err: &io::Error) {
+    // Only this connection failed, e.g. the client reset it before it was accepted.
+    if is_connection_error(err) {
+        return;
+    }
+    eprintln!("{server} server failed to accept a connection: {err}");
+    tokio::time::sleep(ACCEPT_ERROR_DELAY).await;
+}
+
+// Whether an error of `accept` only concerns the connection being accepted.
+fn is_connection_error(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        ErrorKind::ConnectionRefused | ErrorKind::ConnectionAborted | ErrorKind::ConnectionReset
+    )
+}
@@ -0,0 +1,157 @@
+//! Actor-mode service execution.
+//!
+//! With `#[multi_rpc_impl(actor)]`, the service is owned by a single task instead of being shared
+//! behind an `Arc<RwLock<S>>`. The generated protocol adapters turn every call into a typed
+//! request message, send it over a bounded `mpsc` channel and await the reply on a `oneshot`
+//! channel.
+//!
+//! Compared to the shared mode, this means:
+//! - Requests are handled one at a time, in the order they arrived, whichever protocol they came
+//!   from. Mutating calls can't be starved or reordered.
+//! - No lock is held across the `.await` points of a method. A slow call only delays the
+//!   requests queued behind it in the channel, not the servers themselves.
+//! - The queue is bounded. Once it is full, adapters wait for space, which applies backpressure
+//!   to the clients. The current depth can be observed through [`ActorHandle::queue_depth`].
+//!
+//! Use [`ServerBuilder::actor`](crate::builder::ServerBuilder::actor) to launch such a service.
+
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+
+use futures::FutureExt;
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tokio::sync::oneshot;
+
+use crate::error::ProtocolError;
+
+/// A service that runs as an actor, receiving requests of type `M`.
+///
+/// Implemented by `#[multi_rpc_impl(actor)]`, which also generates the message enum `M`.
+pub trait Actor<M>: Sized + Send + 'static {
+    /// Handles `requests` until every sender has been dropped.
+    fn run(self, requests: mpsc::Receiver<M>) -> impl Future<Output = ()> + Send;
+}
+
+/// A cloneable handle used by the protocol adapters to send requests to an actor.
+pub struct ActorHandle<M> {
+    sender: mpsc::Sender<M>,
+}
+
+// Implemented by hand so that `M` doesn't need to be `Clone`.
+impl<M> Clone for ActorHandle<M> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<M> ActorHandle<M> {
+    pub(crate) fn new(sender: mpsc::Sender<M>) -> Self {
+        Self { sender }
+    }
+
+    /// Sends the message built by `make` and waits for the actor's reply.
+    ///
+    /// Waits for space in the queue if it is full. Fails if the actor has stopped, or if the
+    /// service panicked while handling the request.
+    pub async fn call<R>(
+        &self,
+        make: impl FnOnce(oneshot::Sender<R>) -> M,
+    ) -> Result<R, ActorError> {
+        let (reply, response) = oneshot::channel();
+        if self.sender.send(make(reply)).await.is_err() {
+            return Err(ActorError::Stopped);
+        }
+        response.await.map_err(|_| ActorError::Panicked)
+    }
+
+    /// The number of requests waiting to be handled by the actor.
+    pub fn queue_depth(&self) -> usize {
+        self.sender.max_capacity() - self.sender.capacity()
+    }
+
+    /// The maximum number of requests that can wait in the queue.
+    pub fn queue_capacity(&self) -> usize {
+        self.sender.max_capacity()
+    }
+}
+
+/// The reason an actor couldn't answer a call.
+///
+/// The REST and JSON-RPC adapters answer with the error's status and code. tarpc has no error
+/// responses, so its adapter fails the calls of the connection, like a panicking method in the
+/// shared mode.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActorError {
+    /// The actor task has stopped, e.g. because the runtime is shutting down.
+    #[error("the service has stopped")]
+    Stopped,
+    /// The service panicked while handling the request.
+    #[error("the service panicked while handling the request")]
+    Panicked,
+}
+
+impl ProtocolError for ActorError {
+    fn http_status(&self) -> u16 {
+        match self {
+            ActorError::Stopped => 503,
+            ActorError::Panicked => 500,
+        }
+    }
+
+    fn jsonrpc_code(&self) -> i32 {
+        match self {
+            ActorError::Stopped => -32053,
+            ActorError::Panicked => -32603,
+        }
+    }
+
+    fn jsonrpc_errors() -> Vec<(i32, &'static str)> {
+        vec![
+            (-32053, "The service has stopped"),
+            (-32603, "The service panicked"),
+        ]
+    }
+}
+
+/// Runs `fut` and sends its output to `reply`.
+///
+/// Used by the generated actor loop. A panicking method drops `reply`, which fails the pending
+/// call, while the actor keeps serving the next requests. A caller that has gone away in the
+/// meantime is ignored.
+pub async fn respond<R>(reply: oneshot::Sender<R>, fut: impl Future<Output = R>) {
+    if let Ok(value) = AssertUnwindSafe(fut).catch_unwind().await {
+        let _ = reply.send(value);
+    }
+}
+
+/// Runs the sync `call` with the actor's `service` on tokio's blocking thread pool, sends its
+/// output to `reply` and hands the service back.
+///
+/// Used by the generated actor loop for `#[blocking]` methods, which works on any runtime,
+/// including the current-thread one. As with [`respond`], a panicking method only fails the
+/// pending call. Returns `None` if the blocking task was cancelled because the runtime is
+/// shutting down, which stops the actor.
+pub async fn respond_blocking<S, R>(
+    service: S,
+    reply: oneshot::Sender<R>,
+    call: impl FnOnce(&mut S) -> R + Send + 'static,
+) -> Option<S>
+where
+    S: Send + 'static,
+    R: Send + 'static,
+{
+    let (service, output) = tokio::task::spawn_blocking(move || {
+        let mut service = service;
+        let output = std::panic::catch_unwind(AssertUnwindSafe(|| call(&mut service)));
+        (service, output)
+    })
+    .await
+    .ok()?;
+    if let Ok(value) = output {
+        let _ = reply.send(value);
+    }
+    Some(service)
+}
@@ -0,0 +1,85 @@
+//! Binary arguments over JSON-RPC.
+//!
+//! JSON has no binary type: serde encodes `Vec<u8>` and `bytes::Bytes` as arrays of numbers,
+//! which the JSON-RPC server always accepts. Arguments bound to a `multipart(...)` group of
+//! `#[rest]` receive file contents over REST, and are accepted as base64 strings as well.
+
+use std::fmt;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use jsonrpsee::types::error::ErrorCode;
+use jsonrpsee::types::ErrorObjectOwned;
+use serde::de;
+use serde::Deserialize;
+use serde::Deserializer;
+
+/// A `Vec<u8>` or `bytes::Bytes` argument as received over JSON-RPC, before the adapter knows
+/// whether the argument accepts base64.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BinaryParam<T> {
+    /// An array of bytes.
+    Bytes(T),
+    /// A string, which is decoded as base64 for the arguments that accept it.
+    Base64(String),
+}
+
+impl<T: From<Vec<u8>>> BinaryParam<T> {
+    /// The bytes of the argument, decoding a base64 string if `base64` is accepted, or else an
+    /// `Invalid params` error.
+    pub fn decode(self, base64: bool) -> Result<T, ErrorObjectOwned> {
+        let invalid_params = |message: String| {
+            ErrorObjectOwned::owned(ErrorCode::InvalidParams.code(), message, None::<()>)
+        };
+        match self {
+            BinaryParam::Bytes(bytes) => Ok(bytes),
+            BinaryParam::Base64(value) if base64 => STANDARD
+                .decode(value)
+                .map(T::from)
+                .map_err(|err| invalid_params(format!("invalid base64: {}", err))),
+            BinaryParam::Base64(_) => Err(invalid_params(
+                "invalid type: string, expected an array of bytes".to_string(),
+            )),
+        }
+    }
+}
+
+impl<'de, T: From<Vec<u8>>> Deserialize<'de> for BinaryParam<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(BinaryParamVisitor(std::marker::PhantomData))
+    }
+}
+
+struct BinaryParamVisitor<T>(std::marker::PhantomData<T>);
+
+impl<'de, T: From<Vec<u8>>> de::Visitor<'de> for BinaryParamVisitor<T> {
+    type Value = BinaryParam<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of bytes or a base64 string")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(BinaryParam::Base64(value.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<Self::Value, E> {
+        Ok(BinaryParam::Base64(value))
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+        Ok(BinaryParam::Bytes(T::from(value.to_vec())))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(BinaryParam::Bytes(T::from(value)))
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(BinaryParam::Bytes(T::from(bytes)))
+    }
+}
@@ -3,7 +3,7 @@
 //!
 //! When you use `multi-rpc`, your single service object is shared across all the different
 //! protocol servers you enable (e.g., tarpc, REST, jsonrpsee). To make this safe and efficient,
-//! we rely on two key synchronization primitives: `Arc` and `Mutex`.
+//! we rely on two key synchronization primitives: `Arc` and `RwLock`.
 //!
 //! # `Arc` for Shared Ownership
 //! The `std::sync::Arc` (Atomically Reference Counted) enables your single service instance to be
@@ -12,15 +12,25 @@
 //! prevents the need to duplicate your service's state for each server and ensures all requests
 //! are handled by the same, consistent logic.
 //!
-//! # `Mutex` for Thread-Safe Access
-//! The `tokio::sync::Mutex` provides exclusive, thread-safe access to your service object.
-//! While `Arc` allows multiple threads to own a reference, it doesn't prevent them from
-//! trying to modify the data at the same time. The `Mutex` ensures that only one server
-//! task can access or modify the service's state at any given moment. This is crucial for
-//! methods that take `&mut self` as it prevents data races and keeps your application's
-//! state consistent. We use the `tokio` version of `Mutex` because it works seamlessly
-//! with asynchronous code, allowing locks to be held across `.await` points without blocking
-//! the entire thread.
+//! # `RwLock` for Thread-Safe Access
+//! The `tokio::sync::RwLock` provides thread-safe access to your service object. While `Arc`
+//! allows multiple threads to own a reference, it doesn't prevent them from trying to modify
+//! the data at the same time. The generated adapters pick the lock mode from each method's
+//! receiver:
+//! - Methods that take `&self` acquire a read guard. Any number of them run concurrently,
+//!   across all protocols and connections.
+//! - Methods that take `&mut self` acquire the write guard, which waits for the running calls
+//!   to finish and gives the method exclusive access. This prevents data races and keeps your
+//!   application's state consistent.
+//!
+//! We use the `tokio` version of `RwLock` because it works seamlessly with asynchronous code,
+//! allowing guards to be held across `.await` points without blocking the entire thread. It is
+//! also fair: once a `&mut self` call is waiting, new `&self` calls queue behind it, so writers
+//! are never starved by a steady stream of readers.
+//!
+//! Services without `&mut self` methods are locked the same way instead of being shared as a
+//! plain `Arc<S>`, so that every service has the same [`SharedService`] handle. Their calls
+//! only ever take read guards, which don't wait on each other.
 //!
 //! # Using `ServerBuilder`
 //! You use the `ServerBuilder` to set up your server:
@@ -28,21 +38,39 @@
 //! 2. Use `add_protocol` to specify the protocols and network addresses for each server you want to run.
 //! 3. Call `build()` to create the servers.
 //! 4. Finally, call `run()` on the resulting `ServerRunner` to start listening for requests.
+//!
+//! # Actor Mode
+//! For services implemented with `#[multi_rpc_impl(actor)]`, create the builder with
+//! [`ServerBuilder::actor`] instead. The service is then owned by a dedicated task and the
+//! servers receive an [`ActorHandle`] to send it requests, see the [`actor`](crate::actor)
+//! module.
 
 use std::future::Future;
+use std::io;
+use std::marker::PhantomData;
 use std::pin::Pin;
 use std::sync::Arc;
 
-use tokio::sync::Mutex;
+use tokio::sync::mpsc;
+use tokio::sync::RwLock;
 
+use crate::actor::Actor;
+use crate::actor::ActorHandle;
 use crate::runner::ServerRunner;
 
 pub type ServerTask = Pin<Box<dyn Future<Output = ()> + Send>>;
-pub type ServerTaskFactory<S> = Box<dyn FnOnce(Arc<Mutex<S>>) -> ServerTask + Send>;
+/// The handle to a service shared by all servers.
+pub type SharedService<S> = Arc<RwLock<S>>;
+pub type ServerTaskFactory<H> = Box<dyn FnOnce(H) -> ServerTask + Send>;
 
-pub struct ServerBuilder<S> {
-    service: Arc<Mutex<S>>,
-    task_factories: Vec<ServerTaskFactory<S>>,
+/// Configures the servers for service `S`, which they reach through a handle `H`.
+pub struct ServerBuilder<S, H = SharedService<S>> {
+    service: H,
+    task_factories: Vec<ServerTaskFactory<H>>,
+    actor_task: Option<ServerTask>,
+    // An invalid setting, reported by `build`.
+    error: Option<io::Error>,
+    _service: PhantomData<fn() -> S>,
 }
 
 impl<S> ServerBuilder<S>
@@ -51,23 +79,66 @@ where
 {
     pub fn new(service: S) -> Self {
         Self {
-            service: Arc::new(Mutex::new(service)),
+            service: Arc::new(RwLock::new(service)),
+            task_factories: Vec::new(),
+            actor_task: None,
+            error: None,
+            _service: PhantomData,
+        }
+    }
+}
+
+impl<S, M> ServerBuilder<S, ActorHandle<M>>
+where
+    S: Actor<M>,
+    M: Send + 'static,
+{
+    /// Creates a builder for a service implemented with `#[multi_rpc_impl(actor)]`.
+    ///
+    /// At most `queue_capacity` requests wait for the actor; further calls wait for space. The
+    /// capacity must be at least 1, otherwise [`build`](Self::build) fails.
+    pub fn actor(service: S, queue_capacity: usize) -> Self {
+        let error = (queue_capacity == 0).then(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the actor's queue capacity must be at least 1",
+            )
+        });
+        let (sender, requests) = mpsc::channel(queue_capacity.max(1));
+        Self {
+            service: ActorHandle::new(sender),
             task_factories: Vec::new(),
+            actor_task: Some(Box::pin(service.run(requests))),
+            error,
+            _service: PhantomData,
         }
     }
+}
 
+impl<S, H> ServerBuilder<S, H>
+where
+    H: Clone + Send + 'static,
+{
     /// Adds a protocol's server task factory to the builder.
     pub fn add_protocol<F>(mut self, factory: F) -> Self
     where
-        F: FnOnce(Arc<Mutex<S>>) -> ServerTask + Send + 'static,
+        F: FnOnce(H) -> ServerTask + Send + 'static,
     {
         self.task_factories.push(Box::new(factory));
         self
     }
 
+    /// The handle the servers use to reach the service, e.g. to observe the actor's queue depth.
+    pub fn service(&self) -> &H {
+        &self.service
+    }
+
     pub fn build(self) -> Result<ServerRunner, std::io::Error> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
         println!("🚀 Launching servers...");
-        let handles = self
+        let mut handles: Vec<_> = self
             .task_factories
             .into_iter()
             .map(|task_fn| {
@@ -75,6 +146,9 @@ where
                 tokio::spawn(task)
             })
             .collect();
+        if let Some(actor_task) = self.actor_task {
+            handles.push(tokio::spawn(actor_task));
+        }
         Ok(ServerRunner { handles })
     }
 }
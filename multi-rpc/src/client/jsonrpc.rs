@@ -0,0 +1,62 @@
+use futures::future::BoxFuture;
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::params::ArrayParams;
+
+use crate::client::ClientError;
+use crate::client::Transport;
+
+/// A [`Transport`] over any jsonrpsee client, e.g. an `HttpClient` or a `WsClient`.
+#[derive(Clone, Debug)]
+pub struct JsonRpcTransport<C> {
+    client: C,
+    method_name: fn(&str) -> &str,
+}
+
+impl<C> JsonRpcTransport<C> {
+    /// Creates a transport that calls the methods under their Rust names. The generated
+    /// `{Trait}Remote::jsonrpc` constructor uses the JSON-RPC names instead.
+    pub fn new(client: C) -> Self {
+        Self {
+            client,
+            method_name: |method| method,
+        }
+    }
+
+    /// Maps the Rust method names to JSON-RPC method names, for traits renaming their methods
+    /// with `#[jsonrpc(...)]`. Pass the generated `jsonrpc_method_name` of the trait's protocols
+    /// module.
+    pub fn with_method_names(mut self, method_name: fn(&str) -> &str) -> Self {
+        self.method_name = method_name;
+        self
+    }
+}
+
+impl<C> Transport for JsonRpcTransport<C>
+where
+    C: ClientT + Send + Sync,
+{
+    fn call<'a>(
+        &'a self,
+        method: &'a str,
+        params: Vec<serde_json::Value>,
+    ) -> BoxFuture<'a, Result<serde_json::Value, ClientError>> {
+        Box::pin(async move {
+            let mut array = ArrayParams::new();
+            for param in params {
+                array
+                    .insert(param)
+                    .map_err(|err| ClientError::Encode(err.to_string()))?;
+            }
+            self.client
+                .request((self.method_name)(method), array)
+                .await
+                .map_err(|err| match err {
+                    jsonrpsee::core::ClientError::Call(obj) => ClientError::Rpc {
+                        code: obj.code(),
+                        message: obj.message().to_string(),
+                    },
+                    err => ClientError::Transport(err.to_string()),
+                })
+        })
+    }
+}
@@ -0,0 +1,147 @@
+use std::marker::PhantomData;
+
+use futures::future::BoxFuture;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+
+/// Contains the JSON-RPC transport for the generated remote clients.
+#[cfg(feature = "jsonrpsee-client")]
+pub mod jsonrpc;
+/// Contains the runtime support for the generated REST clients.
+#[cfg(feature = "rest-client")]
+pub mod rest;
+
+/// The error returned by the generated clients when a call doesn't produce a response value.
+#[derive(Error, Debug)]
+pub enum ClientError {
+    /// The request couldn't be sent or the response couldn't be received.
+    #[error("transport error: {0}")]
+    Transport(String),
+    /// The arguments couldn't be encoded into a request.
+    #[error("failed to encode request: {0}")]
+    Encode(String),
+    /// The server answered with an HTTP error status.
+    #[error("server responded with status {status}: {body}")]
+    Http {
+        /// The HTTP status code.
+        status: u16,
+        /// The response body, the problem details of the error for the generated REST servers.
+        body: String,
+    },
+    /// The server answered with a JSON-RPC error object.
+    #[error("server responded with error {code}: {message}")]
+    Rpc {
+        /// The JSON-RPC error code.
+        code: i32,
+        /// The error message.
+        message: String,
+    },
+    /// The service method returned an error, received as is and encoded as JSON, e.g. the
+    /// `RpcError` of a method called over tarpc.
+    #[error("service error: {0}")]
+    Service(serde_json::Value),
+    /// The transport can't call the method, e.g. a method without a `#[rest]` route.
+    #[error("method `{0}` is not available over this transport")]
+    UnsupportedMethod(String),
+    /// The response couldn't be decoded into the method's return type.
+    #[error("failed to decode response: {0}")]
+    Decode(String),
+}
+
+/// A connection to a remote service that can call its methods by name.
+///
+/// Used by the `{Trait}Remote` types generated with `#[multi_rpc_trait(remote)]`, which encode
+/// the arguments as positional JSON values. For a method returning `Result<T, E>`, the call
+/// responds with the encoded `T`, and the service error is reported as a `ClientError`: as
+/// [`ClientError::Service`] with the encoded `E` by transports that receive the error itself,
+/// such as tarpc, and as the HTTP status or JSON-RPC error it was sent as by the others.
+///
+/// Implemented by the tarpc clients, the REST clients (`rest-client` feature) and
+/// [`jsonrpc::JsonRpcTransport`] (`jsonrpsee-client` feature).
+pub trait Transport: Send + Sync {
+    /// Calls `method` with `params` and returns the encoded response.
+    fn call<'a>(
+        &'a self,
+        method: &'a str,
+        params: Vec<serde_json::Value>,
+    ) -> BoxFuture<'a, Result<serde_json::Value, ClientError>>;
+}
+
+/// Encodes an argument or response for a [`Transport`].
+pub fn encode<T: Serialize>(value: &T) -> Result<serde_json::Value, ClientError> {
+    serde_json::to_value(value).map_err(|err| ClientError::Encode(err.to_string()))
+}
+
+/// Decodes a response received through a [`Transport`].
+pub fn decode<T: DeserializeOwned>(value: serde_json::Value) -> Result<T, ClientError> {
+    serde_json::from_value(value).map_err(|err| ClientError::Decode(err.to_string()))
+}
+
+/// Decodes the next positional parameter of a [`Transport`] call.
+pub fn param<T: DeserializeOwned>(
+    params: &mut impl Iterator<Item = serde_json::Value>,
+) -> Result<T, ClientError> {
+    let value = params
+        .next()
+        .ok_or_else(|| ClientError::Encode("missing parameter".to_string()))?;
+    serde_json::from_value(value).map_err(|err| ClientError::Encode(err.to_string()))
+}
+
+/// Converts the failure of a remote call into the error type `E` of a method returning
+/// `Result<T, E>`, which must implement `From<ClientError>`.
+///
+/// Used by the generated `{Trait}Remote` types as `(&&FailureMapping::of_result(..)).failure(err)`,
+/// with the `*Failure` traits in scope. Method resolution picks the first trait that applies:
+///
+/// 1. [`DecodeOrConvertFailure`]: a [`ClientError::Service`] is decoded as `E`, e.g. the
+///    `RpcError` returned by a method over tarpc, other failures convert with `From<ClientError>`.
+/// 2. [`ConvertFailure`]: every failure converts with `From<ClientError>`.
+pub struct FailureMapping<E>(PhantomData<E>);
+
+impl<E: From<ClientError>> FailureMapping<E> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+
+    /// The mapping of the error type of a method returning `Result<T, E>`, given as the
+    /// `PhantomData` of its return type, which may be an alias such as `io::Result<T>`.
+    pub fn of_result<T>(_return_ty: PhantomData<Result<T, E>>) -> Self {
+        Self::new()
+    }
+}
+
+impl<E: From<ClientError>> Default for FailureMapping<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The mapping of types that can be decoded and converted from a `ClientError`, see
+/// [`FailureMapping`].
+pub trait DecodeOrConvertFailure<E> {
+    fn failure(&self, err: ClientError) -> E;
+}
+
+impl<E: DeserializeOwned + From<ClientError>> DecodeOrConvertFailure<E> for &FailureMapping<E> {
+    fn failure(&self, err: ClientError) -> E {
+        match err {
+            ClientError::Service(value) => match serde_json::from_value(value) {
+                Ok(err) => err,
+                Err(decode_err) => E::from(ClientError::Decode(decode_err.to_string())),
+            },
+            err => E::from(err),
+        }
+    }
+}
+
+/// The mapping of types that can be converted from a `ClientError`, see [`FailureMapping`].
+pub trait ConvertFailure<E> {
+    fn failure(&self, err: ClientError) -> E;
+}
+
+impl<E: From<ClientError>> ConvertFailure<E> for FailureMapping<E> {
+    fn failure(&self, err: ClientError) -> E {
+        E::from(err)
+    }
+}
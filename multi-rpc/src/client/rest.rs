@@ -0,0 +1,292 @@
+//! Runtime support for the `{Trait}RestClient` types generated with the `rest-client` feature.
+//!
+//! The generated clients build each request from the method's `#[rest(...)]` attribute and use
+//! the helpers in this module to send it and decode the response.
+
+use bytes::Bytes;
+use futures::StreamExt;
+use percent_encoding::utf8_percent_encode;
+use percent_encoding::AsciiSet;
+use percent_encoding::CONTROLS;
+use reqwest::multipart::Form;
+use reqwest::multipart::Part;
+use reqwest::RequestBuilder;
+use reqwest::Response;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::client::ClientError;
+use crate::RestResponse;
+use crate::Streaming;
+
+// The characters that must be escaped in a path segment.
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+// Wildcards such as `{*path}` capture the rest of the path, slashes included.
+const PATH_WILDCARD: &AsciiSet = &PATH_SEGMENT.remove(b'/');
+
+/// The base URL and HTTP client shared by the methods of a generated REST client.
+#[derive(Clone, Debug)]
+pub struct RestClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl RestClient {
+    /// Creates a client for the server at `base_url`, e.g. `http://127.0.0.1:9002`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_client(reqwest::Client::new(), base_url)
+    }
+
+    /// Creates a client that sends its requests through a preconfigured `reqwest::Client`.
+    pub fn with_client(http: reqwest::Client, base_url: impl Into<String>) -> Self {
+        let mut base_url = base_url.into();
+        while base_url.ends_with('/') {
+            base_url.pop();
+        }
+        Self { http, base_url }
+    }
+
+    /// Starts a request for `path`, relative to the base URL.
+    pub fn request(&self, method: &str, path: &str) -> RequestBuilder {
+        // The method has been checked against the known HTTP methods at compile time.
+        let method = reqwest::Method::from_bytes(method.as_bytes()).expect("invalid HTTP method");
+        self.http
+            .request(method, format!("{}{}", self.base_url, path))
+    }
+}
+
+/// Formats a path parameter as a percent-encoded path segment.
+pub fn path_segment<T: Serialize>(value: &T) -> Result<String, ClientError> {
+    Ok(utf8_percent_encode(&path_value(value)?, PATH_SEGMENT).to_string())
+}
+
+/// Formats a wildcard path parameter, keeping its slashes.
+pub fn path_wildcard<T: Serialize>(value: &T) -> Result<String, ClientError> {
+    Ok(utf8_percent_encode(&path_value(value)?, PATH_WILDCARD).to_string())
+}
+
+fn path_value<T: Serialize>(value: &T) -> Result<String, ClientError> {
+    scalar_value(value, "path parameters")?
+        .ok_or_else(|| ClientError::Encode("path parameters can't be `null`".to_string()))
+}
+
+/// Adds the header `name`, unless `value` is `None`.
+pub fn with_header<T: Serialize>(
+    request: RequestBuilder,
+    name: &str,
+    value: &T,
+) -> Result<RequestBuilder, ClientError> {
+    Ok(match scalar_value(value, "headers")? {
+        Some(value) => request.header(name, value),
+        None => request,
+    })
+}
+
+/// Formats the cookie `name=value`, `None` if `value` is `None`.
+pub fn cookie<T: Serialize>(name: &str, value: &T) -> Result<Option<String>, ClientError> {
+    Ok(scalar_value(value, "cookies")?.map(|value| format!("{}={}", name, value)))
+}
+
+/// Adds a `Cookie` header with the `cookies` formatted by [`cookie`], if there are any.
+pub fn with_cookies(request: RequestBuilder, cookies: Vec<Option<String>>) -> RequestBuilder {
+    let cookies: Vec<String> = cookies.into_iter().flatten().collect();
+    if cookies.is_empty() {
+        request
+    } else {
+        request.header(reqwest::header::COOKIE, cookies.join("; "))
+    }
+}
+
+/// Adds the file field `name` with `contents`, unless they're `None`.
+pub fn multipart_file<T: AsRef<[u8]>>(form: Form, name: &str, contents: Option<&T>) -> Form {
+    match contents {
+        Some(contents) => form.part(
+            name.to_string(),
+            Part::bytes(contents.as_ref().to_vec()).file_name(name.to_string()),
+        ),
+        None => form,
+    }
+}
+
+/// Adds the text field `name`, unless `value` is `None`. Strings, numbers and booleans are sent
+/// as is, other values as JSON.
+pub fn multipart_text<T: Serialize>(
+    form: Form,
+    name: &str,
+    value: &T,
+) -> Result<Form, ClientError> {
+    let text = match serde_json::to_value(value) {
+        Ok(serde_json::Value::Null) => return Ok(form),
+        Ok(serde_json::Value::String(s)) => s,
+        Ok(other) => other.to_string(),
+        Err(err) => return Err(ClientError::Encode(err.to_string())),
+    };
+    Ok(form.text(name.to_string(), text))
+}
+
+// The text of a path parameter, header or cookie, `None` for `null`.
+fn scalar_value<T: Serialize>(value: &T, kind: &str) -> Result<Option<String>, ClientError> {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => Ok(Some(s)),
+        Ok(serde_json::Value::Number(n)) => Ok(Some(n.to_string())),
+        Ok(serde_json::Value::Bool(b)) => Ok(Some(b.to_string())),
+        Ok(serde_json::Value::Null) => Ok(None),
+        Ok(other) => Err(ClientError::Encode(format!(
+            "{} must be strings, numbers or booleans, got `{}`",
+            kind, other
+        ))),
+        Err(err) => Err(ClientError::Encode(err.to_string())),
+    }
+}
+
+/// Sends `request` and decodes the JSON response body.
+///
+/// A response without a body, e.g. `204 No Content`, is decoded as `null`, so methods returning
+/// `()` or an `Option` accept it.
+pub async fn send_json<T: DeserializeOwned>(request: RequestBuilder) -> Result<T, ClientError> {
+    let body = send(request)
+        .await?
+        .bytes()
+        .await
+        .map_err(|err| ClientError::Decode(err.to_string()))?;
+    let decoded = if body.is_empty() {
+        serde_json::from_value(serde_json::Value::Null)
+    } else {
+        serde_json::from_slice(&body)
+    };
+    decoded.map_err(|err| ClientError::Decode(err.to_string()))
+}
+
+/// The return type of a method that `produces` a raw body, read from the response as is.
+pub trait FromRawBody: Sized {
+    fn from_raw_body(body: Bytes) -> Result<Self, ClientError>;
+}
+
+impl FromRawBody for String {
+    fn from_raw_body(body: Bytes) -> Result<Self, ClientError> {
+        String::from_utf8(body.to_vec()).map_err(|err| ClientError::Decode(err.to_string()))
+    }
+}
+
+impl FromRawBody for Vec<u8> {
+    fn from_raw_body(body: Bytes) -> Result<Self, ClientError> {
+        Ok(body.to_vec())
+    }
+}
+
+impl FromRawBody for Bytes {
+    fn from_raw_body(body: Bytes) -> Result<Self, ClientError> {
+        Ok(body)
+    }
+}
+
+impl<T: FromRawBody> FromRawBody for RestResponse<T> {
+    fn from_raw_body(body: Bytes) -> Result<Self, ClientError> {
+        T::from_raw_body(body).map(RestResponse::new)
+    }
+}
+
+/// Sends `request` and reads the raw response body, see [`FromRawBody`].
+pub async fn send_raw<T: FromRawBody>(request: RequestBuilder) -> Result<T, ClientError> {
+    let body = send(request)
+        .await?
+        .bytes()
+        .await
+        .map_err(|err| ClientError::Decode(err.to_string()))?;
+    T::from_raw_body(body)
+}
+
+/// Sends `request` and decodes the Server-Sent Events of a streaming endpoint.
+///
+/// Each event's `data` is decoded as one JSON item. Events without data, such as the keep-alive
+/// comments, are skipped.
+pub async fn send_sse<T>(
+    request: RequestBuilder,
+) -> Result<Streaming<Result<T, ClientError>>, ClientError>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    let body = Box::pin(send(request).await?.bytes_stream());
+    let items = futures::stream::unfold((body, Vec::new()), |(mut body, mut buffer)| async move {
+        loop {
+            if let Some(len) = event_len(&buffer) {
+                let event: Vec<u8> = buffer.drain(..len).collect();
+                let event = String::from_utf8_lossy(&event);
+                let data: Vec<&str> = event
+                    .split(['\r', '\n'])
+                    .filter_map(|line| line.strip_prefix("data:"))
+                    .map(|data| data.strip_prefix(' ').unwrap_or(data))
+                    .collect();
+                if data.is_empty() {
+                    continue;
+                }
+                let item = serde_json::from_str(&data.join("\n"))
+                    .map_err(|err| ClientError::Decode(err.to_string()));
+                return Some((item, (body, buffer)));
+            }
+            match body.next().await {
+                Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                Some(Err(err)) => {
+                    return Some((Err(ClientError::Transport(err.to_string())), (body, buffer)))
+                }
+                None => return None,
+            }
+        }
+    });
+    Ok(Streaming::new(items))
+}
+
+// The length of the first event in `buffer`, up to and including the blank line that ends it.
+//
+// Lines end with `\r\n`, `\n` or `\r`. A `\r` at the end of the buffer ends its line, even if
+// it turns out to be the start of a `\r\n`: the `\n` is then read as an empty event.
+fn event_len(buffer: &[u8]) -> Option<usize> {
+    let mut line_start = 0;
+    let mut i = 0;
+    while i < buffer.len() {
+        let terminator_len = match buffer[i] {
+            b'\r' if buffer.get(i + 1) == Some(&b'\n') => 2,
+            b'\r' | b'\n' => 1,
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        if i == line_start {
+            return Some(i + terminator_len);
+        }
+        i += terminator_len;
+        line_start = i;
+    }
+    None
+}
+
+// Sends `request`, turning HTTP error statuses into `ClientError::Http`.
+async fn send(request: RequestBuilder) -> Result<Response, ClientError> {
+    let response = request
+        .send()
+        .await
+        .map_err(|err| ClientError::Transport(err.to_string()))?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(response)
+    } else {
+        let body = response.text().await.unwrap_or_default();
+        Err(ClientError::Http {
+            status: status.as_u16(),
+            body,
+        })
+    }
+}
@@ -0,0 +1,23 @@
+//! Serialization formats of the tarpc transport.
+//!
+//! The generated `tarpc_tcp_with_codec(addr, codec)` server factory and the
+//! `connect_tarpc(addr, codec)` client helper take the same [`TarpcCodec`], so a client and a
+//! server configured from one value always agree on the format. `tarpc_tcp(addr)` uses JSON.
+
+/// The format tarpc messages are serialized with.
+///
+/// JSON and Bincode are always available. MessagePack and CBOR require the `tarpc-messagepack`
+/// and `tarpc-cbor` features.
+///
+/// Bincode isn't self-describing, so it can't serialize types that rely on knowing the
+/// format of the input, such as `serde_json::Value` or `#[serde(untagged)]` enums.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TarpcCodec {
+    #[default]
+    Json,
+    Bincode,
+    #[cfg(feature = "tarpc-messagepack")]
+    MessagePack,
+    #[cfg(feature = "tarpc-cbor")]
+    Cbor,
+}
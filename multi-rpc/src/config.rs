@@ -0,0 +1,317 @@
+//! Server configuration for the JSON-RPC server.
+//!
+//! The generated `jsonrpsee_with_config(addr, config)` factory serves a service with a
+//! [`JsonRpcConfig`], while `jsonrpsee(addr)` uses [`JsonRpcConfig::default`], i.e. jsonrpsee's
+//! own defaults.
+//!
+//! The server stores a [`RequestContext`] with the client's address and the request headers in the
+//! extensions of every request, which the generated adapters pass to methods taking a
+//! `&RequestContext`.
+
+use std::fmt;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+
+use jsonrpsee::server::serve_with_graceful_shutdown;
+use jsonrpsee::server::stop_channel;
+use jsonrpsee::server::BatchRequestConfig;
+use jsonrpsee::server::HttpBody;
+use jsonrpsee::server::HttpRequest;
+use jsonrpsee::server::HttpResponse;
+use jsonrpsee::server::PingConfig;
+use jsonrpsee::server::Server;
+use jsonrpsee::server::ServerConfig;
+use jsonrpsee::server::ServerHandle;
+use jsonrpsee::Methods;
+use tokio::net::TcpListener;
+use tower::Layer;
+use tower::Service;
+
+use crate::accept;
+use crate::context::Identity;
+use crate::context::RequestContext;
+
+/// The transports accepted by the JSON-RPC server.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum JsonRpcTransports {
+    /// HTTP requests and WebSocket connections.
+    #[default]
+    HttpAndWs,
+    /// Only HTTP requests, so subscriptions are unavailable.
+    HttpOnly,
+    /// Only WebSocket connections.
+    WsOnly,
+}
+
+/// Configures the JSON-RPC server started by `jsonrpsee_with_config`.
+///
+/// Settings left unset keep jsonrpsee's defaults.
+///
+/// ```rust,ignore
+/// let config = JsonRpcConfig::new()
+///     .transports(JsonRpcTransports::HttpOnly)
+///     .max_request_body_size(1024 * 1024)
+///     .max_batch_size(16)
+///     .path("/rpc");
+/// ServerBuilder::new(service).add_protocol(greeter_for_my_greeter_impls::jsonrpsee_with_config(addr, config));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct JsonRpcConfig {
+    transports: JsonRpcTransports,
+    max_request_body_size: Option<u32>,
+    max_response_body_size: Option<u32>,
+    max_connections: Option<u32>,
+    batch_requests: Option<BatchRequestConfig>,
+    ping_interval: Option<Duration>,
+    path: Option<String>,
+    authenticator: Option<Authenticator>,
+}
+
+// Resolves the identity of the caller from the context of a request.
+type Authenticate = dyn Fn(&RequestContext) -> Option<Identity> + Send + Sync;
+
+#[derive(Clone)]
+struct Authenticator(Arc<Authenticate>);
+
+impl fmt::Debug for Authenticator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Authenticator")
+    }
+}
+
+impl JsonRpcConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Selects the accepted transports.
+    pub fn transports(mut self, transports: JsonRpcTransports) -> Self {
+        self.transports = transports;
+        self
+    }
+
+    /// The maximum size of a request body in bytes.
+    pub fn max_request_body_size(mut self, size: u32) -> Self {
+        self.max_request_body_size = Some(size);
+        self
+    }
+
+    /// The maximum size of a response body in bytes.
+    pub fn max_response_body_size(mut self, size: u32) -> Self {
+        self.max_response_body_size = Some(size);
+        self
+    }
+
+    /// The maximum number of connections served at once.
+    pub fn max_connections(mut self, max: u32) -> Self {
+        self.max_connections = Some(max);
+        self
+    }
+
+    /// Rejects batch requests.
+    pub fn disable_batch_requests(mut self) -> Self {
+        self.batch_requests = Some(BatchRequestConfig::Disabled);
+        self
+    }
+
+    /// The maximum number of calls in a batch request.
+    pub fn max_batch_size(mut self, size: u32) -> Self {
+        self.batch_requests = Some(BatchRequestConfig::Limit(size));
+        self
+    }
+
+    /// Pings WebSocket clients at this interval.
+    pub fn ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = Some(interval);
+        self
+    }
+
+    /// Serves the JSON-RPC endpoint at `path` only, answering `404 Not Found` elsewhere.
+    /// By default, every path is served.
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Resolves the [`Identity`] of the caller of every request with `authenticate`, from the
+    /// client's address and the request headers, e.g. an `Authorization` header. Over WebSocket,
+    /// the handshake request is authenticated once for the whole connection.
+    ///
+    /// The identity is available to methods through [`RequestContext::identity`]. Requests
+    /// without one are still served, methods decide whether they require it.
+    pub fn authenticate(
+        mut self,
+        authenticate: impl Fn(&RequestContext) -> Option<Identity> + Send + Sync + 'static,
+    ) -> Self {
+        self.authenticator = Some(Authenticator(Arc::new(authenticate)));
+        self
+    }
+
+    /// Starts a jsonrpsee server on `addr` with this configuration and serves `methods` until
+    /// the returned future is dropped, e.g. by aborting its task, which closes the open
+    /// connections as well.
+    ///
+    /// Errors accepting a connection are logged, and the server waits a second before accepting
+    /// again unless only that connection failed.
+    pub async fn serve(self, addr: SocketAddr, methods: impl Into<Methods>) -> std::io::Result<()> {
+        let mut config = ServerConfig::builder();
+        match self.transports {
+            JsonRpcTransports::HttpAndWs => {}
+            JsonRpcTransports::HttpOnly => config = config.http_only(),
+            JsonRpcTransports::WsOnly => config = config.ws_only(),
+        }
+        if let Some(size) = self.max_request_body_size {
+            config = config.max_request_body_size(size);
+        }
+        if let Some(size) = self.max_response_body_size {
+            config = config.max_response_body_size(size);
+        }
+        if let Some(max) = self.max_connections {
+            config = config.max_connections(max);
+        }
+        if let Some(batch_requests) = self.batch_requests {
+            config = config.set_batch_request_config(batch_requests);
+        }
+        if let Some(interval) = self.ping_interval {
+            config = config.enable_ws_ping(PingConfig::new().ping_interval(interval));
+        }
+
+        let http_middleware = tower::ServiceBuilder::new()
+            .option_layer(self.path.clone().map(|path| PathFilterLayer(path.into())));
+        let service_builder = Server::builder()
+            .set_config(config.build())
+            .set_http_middleware(http_middleware)
+            .to_service_builder();
+        let methods: Methods = methods.into();
+
+        let listener = TcpListener::bind(addr).await?;
+        println!(
+            "🌐 JSON-RPC (jsonrpsee) server listening on http://{}{}",
+            addr,
+            self.path.as_deref().unwrap_or("")
+        );
+
+        // Connections are accepted here rather than by jsonrpsee's `Server`, which doesn't
+        // expose the client's address to the methods.
+        let (stop_handle, server_handle) = stop_channel();
+        let _stop = StopOnDrop(server_handle);
+        loop {
+            let (socket, peer_addr) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    accept::back_off("JSON-RPC (jsonrpsee)", &err).await;
+                    continue;
+                }
+            };
+            // jsonrpsee's `Server` disables Nagle's algorithm as well.
+            let _ = socket.set_nodelay(true);
+            let service = WithRequestContext {
+                inner: service_builder
+                    .clone()
+                    .build(methods.clone(), stop_handle.clone()),
+                peer_addr,
+                authenticator: self.authenticator.clone(),
+            };
+            tokio::spawn(serve_with_graceful_shutdown(
+                socket,
+                service,
+                stop_handle.clone().shutdown(),
+            ));
+        }
+    }
+}
+
+// Stops the connections of the server when `serve` returns or is dropped, e.g. when
+// `ServerRunner` aborts its task, so that no connection outlives the server.
+struct StopOnDrop(ServerHandle);
+
+impl Drop for StopOnDrop {
+    fn drop(&mut self) {
+        let _ = self.0.stop();
+    }
+}
+
+// Stores the `RequestContext` of every request on a connection in the request extensions.
+#[derive(Clone)]
+struct WithRequestContext<S> {
+    inner: S,
+    peer_addr: SocketAddr,
+    authenticator: Option<Authenticator>,
+}
+
+impl<S, B> Service<HttpRequest<B>> for WithRequestContext<S>
+where
+    S: Service<HttpRequest<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: HttpRequest<B>) -> Self::Future {
+        let mut context = RequestContext::from_http("jsonrpsee", request.headers())
+            .with_peer_addr(self.peer_addr);
+        if let Some(identity) = self
+            .authenticator
+            .as_ref()
+            .and_then(|Authenticator(authenticate)| authenticate(&context))
+        {
+            context = context.with_identity(identity);
+        }
+        request.extensions_mut().insert(context);
+        self.inner.call(request)
+    }
+}
+
+// Answers requests outside of the configured path with `404 Not Found`.
+#[derive(Clone)]
+struct PathFilterLayer(Arc<str>);
+
+impl<S> Layer<S> for PathFilterLayer {
+    type Service = PathFilter<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        PathFilter {
+            inner,
+            path: self.0.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct PathFilter<S> {
+    inner: S,
+    path: Arc<str>,
+}
+
+impl<S, B> Service<HttpRequest<B>> for PathFilter<S>
+where
+    S: Service<HttpRequest<B>, Response = HttpResponse>,
+    S::Future: Send + 'static,
+{
+    type Response = HttpResponse;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<HttpResponse, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: HttpRequest<B>) -> Self::Future {
+        if request.uri().path() == &*self.path {
+            Box::pin(self.inner.call(request))
+        } else {
+            let mut response = HttpResponse::new(HttpBody::from("Not Found\n"));
+            *response.status_mut() = http::StatusCode::NOT_FOUND;
+            Box::pin(async { Ok(response) })
+        }
+    }
+}
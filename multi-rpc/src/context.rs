@@ -0,0 +1,167 @@
+//! The context of the request a service method is serving.
+//!
+//! A method opts in by taking a `&RequestContext` parameter, which isn't part of the wire
+//! params. Every protocol adapter fills it in from the request it received:
+//!
+//! ```rust,ignore
+//! #[multi_rpc_trait]
+//! pub trait Greeter {
+//!     async fn greet(&self, ctx: &RequestContext, name: String) -> String;
+//! }
+//! ```
+//!
+//! | | tarpc | REST (Axum) | JSON-RPC (jsonrpsee) |
+//! |---|---|---|---|
+//! | peer address | the TCP peer | the TCP peer | the TCP peer |
+//! | deadline | the client's deadline | none | none |
+//! | trace id | tarpc's trace id, if the client traces | from the headers | from the headers |
+//! | headers | none | the request headers | the request headers, or the WebSocket handshake's |
+//! | identity | none | an [`Identity`] request extension | [`JsonRpcConfig::authenticate`] |
+//!
+//! Over HTTP, the trace id is the trace id of a W3C `traceparent` header, or else the value of an
+//! `x-request-id` header.
+//!
+//! [`JsonRpcConfig::authenticate`]: crate::config::JsonRpcConfig::authenticate
+
+use std::net::SocketAddr;
+use std::time::Instant;
+
+use http::HeaderMap;
+
+/// The authenticated identity of the caller, e.g. a user name or an API key id.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Identity(pub String);
+
+/// The protocol-agnostic context of a request, see the [module docs](self).
+#[derive(Clone, Debug)]
+pub struct RequestContext {
+    protocol: &'static str,
+    peer_addr: Option<SocketAddr>,
+    deadline: Option<Instant>,
+    trace_id: Option<String>,
+    headers: HeaderMap,
+    identity: Option<Identity>,
+}
+
+impl RequestContext {
+    /// An empty context for a request received over `protocol`.
+    pub fn new(protocol: &'static str) -> Self {
+        Self {
+            protocol,
+            peer_addr: None,
+            deadline: None,
+            trace_id: None,
+            headers: HeaderMap::new(),
+            identity: None,
+        }
+    }
+
+    /// A context for a request received over HTTP with `headers`.
+    pub fn from_http(protocol: &'static str, headers: &HeaderMap) -> Self {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+        // traceparent: {version}-{trace id}-{parent id}-{flags}
+        let trace_id = header("traceparent")
+            .and_then(|traceparent| traceparent.split('-').nth(1))
+            .or_else(|| header("x-request-id"))
+            .map(str::to_string);
+        Self {
+            trace_id,
+            headers: headers.clone(),
+            ..Self::new(protocol)
+        }
+    }
+
+    /// The context of a tarpc request from `peer_addr`.
+    #[cfg(feature = "tarpc")]
+    pub fn from_tarpc(context: &tarpc::context::Context, peer_addr: Option<SocketAddr>) -> Self {
+        let trace_id = context.trace_id();
+        Self {
+            peer_addr,
+            deadline: Some(context.deadline),
+            trace_id: (!trace_id.is_none()).then(|| trace_id.to_string()),
+            ..Self::new("tarpc")
+        }
+    }
+
+    /// The context of a request to the REST server.
+    ///
+    /// The peer address is read from axum's `ConnectInfo`, and the identity from an [`Identity`]
+    /// inserted into the request extensions by an authentication middleware.
+    #[cfg(feature = "rest-axum")]
+    pub fn from_rest_axum(parts: &http::request::Parts) -> Self {
+        use axum::extract::ConnectInfo;
+
+        Self {
+            peer_addr: parts
+                .extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| *addr),
+            identity: parts.extensions.get::<Identity>().cloned(),
+            ..Self::from_http("rest_axum", &parts.headers)
+        }
+    }
+
+    /// The context of a call to the JSON-RPC server, which stores it in the request extensions.
+    #[cfg(feature = "jsonrpsee")]
+    pub fn from_jsonrpsee(extensions: &http::Extensions) -> Self {
+        extensions
+            .get::<RequestContext>()
+            .cloned()
+            .unwrap_or_else(|| Self::new("jsonrpsee"))
+    }
+
+    pub fn with_peer_addr(mut self, peer_addr: SocketAddr) -> Self {
+        self.peer_addr = Some(peer_addr);
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn with_trace_id(mut self, trace_id: impl Into<String>) -> Self {
+        self.trace_id = Some(trace_id.into());
+        self
+    }
+
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    pub fn with_identity(mut self, identity: Identity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// The protocol the request was received over: `tarpc`, `rest_axum` or `jsonrpsee`.
+    pub fn protocol(&self) -> &'static str {
+        self.protocol
+    }
+
+    /// The address of the client.
+    pub fn peer_addr(&self) -> Option<SocketAddr> {
+        self.peer_addr
+    }
+
+    /// The time by which the client stops waiting for the response.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// The id of the trace the request belongs to.
+    pub fn trace_id(&self) -> Option<&str> {
+        self.trace_id.as_deref()
+    }
+
+    /// The request headers, empty for protocols without headers.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// The authenticated identity of the caller.
+    pub fn identity(&self) -> Option<&Identity> {
+        self.identity.as_ref()
+    }
+}
@@ -2,15 +2,137 @@ use serde::Deserialize;
 use serde::Serialize;
 use thiserror::Error;
 
+use crate::client::ClientError;
+use crate::problem::Problem;
+
 /// A general-purpose error type for RPC service methods.
 ///
 /// This enum is intended to be used within the `Result` returned by your service
 /// trait's methods, allowing business logic errors to be serialized and sent to the client.
+///
+/// Every variant maps to an HTTP status for REST and a JSON-RPC error code, see
+/// [`ProtocolError`]. Over tarpc, the error is sent as is.
+///
+/// | Variant | HTTP status | JSON-RPC code |
+/// |---|---|---|
+/// | `InternalError` | 500 | -32603 |
+/// | `NotFound` | 404 | -32004 |
+/// | `InvalidArgument` | 400 | -32602 |
+/// | `Unauthenticated` | 401 | -32001 |
+/// | `PermissionDenied` | 403 | -32003 |
+/// | `Conflict` | 409 | -32009 |
+/// | `Unavailable` | 503 | -32053 |
+/// | `DeadlineExceeded` | 504 | -32054 |
+/// | `RateLimited` | 429 | -32029 |
 #[derive(Error, Debug, Serialize, Deserialize)]
+#[cfg_attr(
+    any(feature = "openapi", feature = "openrpc"),
+    derive(schemars::JsonSchema)
+)]
 pub enum RpcError {
     /// Represents an internal server error or a logic failure.
     #[error("Internal server error: {0}")]
     InternalError(String),
+    /// The requested resource doesn't exist.
+    #[error("Not found: {0}")]
+    NotFound(String),
+    /// The arguments of the call are invalid.
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    /// The caller isn't authenticated.
+    #[error("Unauthenticated: {0}")]
+    Unauthenticated(String),
+    /// The caller isn't allowed to perform the call.
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+    /// The call conflicts with the current state, e.g. the resource already exists.
+    #[error("Conflict: {0}")]
+    Conflict(String),
+    /// The service is temporarily unable to handle the call.
+    #[error("Unavailable: {0}")]
+    Unavailable(String),
+    /// The call didn't complete before its deadline.
+    #[error("Deadline exceeded: {0}")]
+    DeadlineExceeded(String),
+    /// The caller sent too many requests.
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+}
+
+impl RpcError {
+    /// The error for an HTTP error `status`, e.g. as received by a REST client.
+    pub fn from_http_status(status: u16, message: String) -> Self {
+        match status {
+            400 => RpcError::InvalidArgument(message),
+            401 => RpcError::Unauthenticated(message),
+            403 => RpcError::PermissionDenied(message),
+            404 => RpcError::NotFound(message),
+            409 => RpcError::Conflict(message),
+            429 => RpcError::RateLimited(message),
+            503 => RpcError::Unavailable(message),
+            504 => RpcError::DeadlineExceeded(message),
+            _ => RpcError::InternalError(message),
+        }
+    }
+
+    /// The error for a JSON-RPC error `code`, e.g. as received by a JSON-RPC client.
+    pub fn from_jsonrpc_code(code: i32, message: String) -> Self {
+        match code {
+            -32602 => RpcError::InvalidArgument(message),
+            -32001 => RpcError::Unauthenticated(message),
+            -32003 => RpcError::PermissionDenied(message),
+            -32004 => RpcError::NotFound(message),
+            -32009 => RpcError::Conflict(message),
+            -32029 => RpcError::RateLimited(message),
+            -32053 => RpcError::Unavailable(message),
+            -32054 => RpcError::DeadlineExceeded(message),
+            _ => RpcError::InternalError(message),
+        }
+    }
+}
+
+impl ProtocolError for RpcError {
+    fn http_status(&self) -> u16 {
+        match self {
+            RpcError::InternalError(_) => 500,
+            RpcError::NotFound(_) => 404,
+            RpcError::InvalidArgument(_) => 400,
+            RpcError::Unauthenticated(_) => 401,
+            RpcError::PermissionDenied(_) => 403,
+            RpcError::Conflict(_) => 409,
+            RpcError::Unavailable(_) => 503,
+            RpcError::DeadlineExceeded(_) => 504,
+            RpcError::RateLimited(_) => 429,
+        }
+    }
+
+    fn jsonrpc_code(&self) -> i32 {
+        match self {
+            RpcError::InternalError(_) => -32603,
+            RpcError::NotFound(_) => -32004,
+            RpcError::InvalidArgument(_) => -32602,
+            RpcError::Unauthenticated(_) => -32001,
+            RpcError::PermissionDenied(_) => -32003,
+            RpcError::Conflict(_) => -32009,
+            RpcError::Unavailable(_) => -32053,
+            RpcError::DeadlineExceeded(_) => -32054,
+            RpcError::RateLimited(_) => -32029,
+        }
+    }
+
+    fn jsonrpc_errors() -> Vec<(i32, &'static str)> {
+        vec![
+            (-32603, "Internal error"),
+            (-32004, "Not found"),
+            (-32602, "Invalid argument"),
+            (-32001, "Unauthenticated"),
+            (-32003, "Permission denied"),
+            (-32009, "Conflict"),
+            (-32053, "Unavailable"),
+            (-32054, "Deadline exceeded"),
+            (-32029, "Rate limited"),
+        ]
+    }
 }
 
 impl From<Box<dyn std::error::Error + Send + Sync + 'static>> for RpcError {
@@ -19,3 +141,163 @@ impl From<Box<dyn std::error::Error + Send + Sync + 'static>> for RpcError {
         RpcError::InternalError(err.to_string())
     }
 }
+
+/// Recovers the kind of error responses, so `RpcError` can be the error type of remote services.
+impl From<ClientError> for RpcError {
+    fn from(err: ClientError) -> Self {
+        match err {
+            ClientError::Http { status, body } => {
+                // REST errors are problem details, whose `detail` is the error message.
+                let message = match serde_json::from_str::<Problem>(&body) {
+                    Ok(problem) => problem.detail,
+                    Err(_) => body,
+                };
+                RpcError::from_http_status(status, message)
+            }
+            ClientError::Rpc { code, message } => RpcError::from_jsonrpc_code(code, message),
+            ClientError::Transport(message) => RpcError::Unavailable(message),
+            // tarpc sends the error itself.
+            ClientError::Service(value) => serde_json::from_value(value.clone())
+                .unwrap_or_else(|_| RpcError::InternalError(value.to_string())),
+            err => RpcError::InternalError(err.to_string()),
+        }
+    }
+}
+
+/// An error that the REST and JSON-RPC servers report with a specific HTTP status and JSON-RPC
+/// error code.
+///
+/// Errors of types that don't implement this trait are reported as `500 Internal Server Error`
+/// and `-32603 Internal error`. It's usually derived with [`RpcError`](macro@crate::RpcError).
+pub trait ProtocolError {
+    /// The HTTP status of the REST error response.
+    fn http_status(&self) -> u16;
+    /// The code of the JSON-RPC error object.
+    fn jsonrpc_code(&self) -> i32;
+    /// The structured data of the error, sent as the `data` of the JSON-RPC error object.
+    fn data(&self) -> Option<serde_json::Value> {
+        None
+    }
+    /// The JSON-RPC codes the type can report, with a short description of each, listed as the
+    /// `errors` of the OpenRPC document. Empty by default, which leaves `errors` out.
+    fn jsonrpc_errors() -> Vec<(i32, &'static str)>
+    where
+        Self: Sized,
+    {
+        Vec::new()
+    }
+}
+
+/// Maps any error to its HTTP status and JSON-RPC code, through [`ProtocolError`] if the error
+/// implements it and to the internal error otherwise.
+///
+/// Used by the generated adapters as `(&ErrorMapping(&err)).http_status()`, with
+/// [`MapProtocolError`] and [`MapOtherError`] in scope. Method resolution prefers the
+/// `MapProtocolError` impl, which only applies if the error implements `ProtocolError`.
+pub struct ErrorMapping<'a, E>(pub &'a E);
+
+/// The mapping of errors implementing [`ProtocolError`], see [`ErrorMapping`].
+pub trait MapProtocolError {
+    fn http_status(&self) -> u16;
+    fn jsonrpc_code(&self) -> i32;
+    fn data(&self) -> Option<serde_json::Value>;
+}
+
+impl<E: ProtocolError> MapProtocolError for ErrorMapping<'_, E> {
+    fn http_status(&self) -> u16 {
+        self.0.http_status()
+    }
+
+    fn jsonrpc_code(&self) -> i32 {
+        self.0.jsonrpc_code()
+    }
+
+    fn data(&self) -> Option<serde_json::Value> {
+        self.0.data()
+    }
+}
+
+/// The mapping of all other errors, see [`ErrorMapping`].
+pub trait MapOtherError {
+    fn http_status(&self) -> u16;
+    fn jsonrpc_code(&self) -> i32;
+    fn data(&self) -> Option<serde_json::Value>;
+}
+
+impl<E> MapOtherError for &ErrorMapping<'_, E> {
+    fn http_status(&self) -> u16 {
+        500
+    }
+
+    fn jsonrpc_code(&self) -> i32 {
+        -32603
+    }
+
+    fn data(&self) -> Option<serde_json::Value> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::discriminant;
+
+    use super::*;
+
+    // Every variant with its HTTP status and JSON-RPC code, as documented on `RpcError`.
+    fn table() -> Vec<(RpcError, u16, i32)> {
+        let message = || "message".to_string();
+        vec![
+            (RpcError::InternalError(message()), 500, -32603),
+            (RpcError::NotFound(message()), 404, -32004),
+            (RpcError::InvalidArgument(message()), 400, -32602),
+            (RpcError::Unauthenticated(message()), 401, -32001),
+            (RpcError::PermissionDenied(message()), 403, -32003),
+            (RpcError::Conflict(message()), 409, -32009),
+            (RpcError::Unavailable(message()), 503, -32053),
+            (RpcError::DeadlineExceeded(message()), 504, -32054),
+            (RpcError::RateLimited(message()), 429, -32029),
+        ]
+    }
+
+    #[test]
+    fn variants_map_to_their_status_and_code() {
+        for (error, status, code) in table() {
+            assert_eq!(error.http_status(), status, "{error:?}");
+            assert_eq!(error.jsonrpc_code(), code, "{error:?}");
+        }
+    }
+
+    #[test]
+    fn statuses_and_codes_round_trip() {
+        for (error, status, code) in table() {
+            let from_status = RpcError::from_http_status(status, "message".to_string());
+            assert_eq!(discriminant(&from_status), discriminant(&error), "{status}");
+            let from_code = RpcError::from_jsonrpc_code(code, "message".to_string());
+            assert_eq!(discriminant(&from_code), discriminant(&error), "{code}");
+            assert_eq!(from_code.to_string(), error.to_string());
+        }
+    }
+
+    #[test]
+    fn unknown_statuses_and_codes_are_internal_errors() {
+        for status in [418, 502] {
+            let error = RpcError::from_http_status(status, "message".to_string());
+            assert!(matches!(error, RpcError::InternalError(_)), "{status}");
+        }
+        for code in [-32000, -32700] {
+            let error = RpcError::from_jsonrpc_code(code, "message".to_string());
+            assert!(matches!(error, RpcError::InternalError(_)), "{code}");
+        }
+    }
+
+    #[test]
+    fn jsonrpc_errors_list_every_code() {
+        let codes: Vec<_> = RpcError::jsonrpc_errors()
+            .into_iter()
+            .map(|(code, _)| code)
+            .collect();
+        let expected: Vec<_> = table().into_iter().map(|(_, _, code)| code).collect();
+        assert_eq!(codes, expected);
+    }
+}
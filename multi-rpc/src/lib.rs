@@ -3,12 +3,54 @@
 /// The multi-rpc prelude for convenient importing of the most common items.
 pub mod prelude;
 
+/// Contains the handling of the errors of accepting connections.
+#[cfg(any(feature = "tarpc", feature = "jsonrpsee"))]
+pub mod accept;
+/// Contains the actor runtime used by `#[multi_rpc_impl(actor)]` services.
+pub mod actor;
+/// Contains the binary arguments of JSON-RPC methods, accepted as base64 for multipart uploads.
+#[cfg(feature = "jsonrpsee")]
+pub mod binary;
 /// Contains the `ServerBuilder` for configuring and launching servers.
 pub mod builder;
+/// Contains the client-side types shared by the generated clients.
+pub mod client;
+/// Contains the serialization formats of the tarpc transport.
+#[cfg(feature = "tarpc")]
+pub mod codec;
+/// Contains the configuration of the JSON-RPC server.
+#[cfg(feature = "jsonrpsee")]
+pub mod config;
+/// Contains the `RequestContext` that service methods can receive.
+pub mod context;
 /// Contains the error types used by the library.
 pub mod error;
+/// Contains the OpenAPI document support for the REST endpoints.
+#[cfg(feature = "openapi")]
+pub mod openapi;
+/// Contains the OpenRPC document support for the JSON-RPC server.
+#[cfg(feature = "openrpc")]
+pub mod openrpc;
+/// Contains the problem details of the REST server's error responses.
+pub mod problem;
+/// Contains the helpers of the generated REST handlers.
+#[cfg(feature = "rest-axum")]
+pub mod rest_axum;
+mod rest_response;
 /// Contains the `ServerRunner` for managing running server tasks.
 pub mod runner;
+/// Contains the JSON Schemas of the OpenAPI and OpenRPC documents.
+#[cfg(any(feature = "openapi", feature = "openrpc"))]
+pub mod schema;
+mod streaming;
+/// Contains the sessions serving `Streaming<T>` methods over tarpc.
+#[cfg(feature = "tarpc")]
+pub mod tarpc_streaming;
+
+#[cfg(feature = "rest-axum")]
+pub use rest_response::IntoRestResponse;
+pub use rest_response::RestResponse;
+pub use streaming::Streaming;
 
 // --- Public Dependency Re-exports (For Version Safety) ---
 
@@ -17,13 +59,24 @@ pub mod runner;
 pub use axum;
 #[cfg(feature = "jsonrpsee")]
 pub use jsonrpsee;
+#[cfg(feature = "rest-client")]
+pub use reqwest;
+#[cfg(any(feature = "openapi", feature = "openrpc"))]
+pub use schemars;
 // --- Macro Re-exports ---
+/// An attribute to run a sync service method on tokio's blocking thread pool.
+pub use multi_rpc_macros::blocking;
+/// An attribute to configure the JSON-RPC interface of a service trait. Used with the `jsonrpsee` feature.
+pub use multi_rpc_macros::jsonrpc;
 /// A procedural macro to generate protocol-specific server implementations from a trait impl.
 pub use multi_rpc_macros::multi_rpc_impl;
 /// A procedural macro to define a service trait compatible with `multi-rpc`.
 pub use multi_rpc_macros::multi_rpc_trait;
 /// An attribute to expose a trait method as a REST endpoint. Used with the `rest-axum` feature.
 pub use multi_rpc_macros::rest;
+/// A derive macro that implements `error::ProtocolError` from `#[rpc(status = .., code = ..)]`
+/// attributes on the variants of an error enum.
+pub use multi_rpc_macros::RpcError;
 pub use serde;
 #[cfg(feature = "tarpc")]
 pub use tarpc;
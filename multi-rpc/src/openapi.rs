@@ -0,0 +1,78 @@
+//! OpenAPI 3.1 documents for the REST endpoints, generated with the `openapi` feature.
+//!
+//! `#[multi_rpc_impl]` generates an `openapi()` function in the service's impl module, which
+//! describes every `#[rest]` route. The argument and return types are described with JSON Schema
+//! through [`schemars`]. Types that don't implement `schemars::JsonSchema` are described by the
+//! empty schema, see [`SchemaMapping`](crate::schema::SchemaMapping).
+
+use schemars::generate::SchemaSettings;
+use schemars::JsonSchema;
+use schemars::SchemaGenerator;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+
+/// Collects the operations and schemas of an OpenAPI document.
+///
+/// Used by the generated `openapi()` functions. Named types are collected once under
+/// `components/schemas` and referenced from the operations.
+pub struct OpenApi {
+    generator: SchemaGenerator,
+    paths: Map<String, Value>,
+}
+
+impl Default for OpenApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OpenApi {
+    pub fn new() -> Self {
+        // OpenAPI 3.1 schemas are JSON Schema 2020-12.
+        let settings = SchemaSettings::draft2020_12().with(|settings| {
+            settings.definitions_path = "/components/schemas".into();
+            settings.meta_schema = None;
+        });
+        Self {
+            generator: settings.into_generator(),
+            paths: Map::new(),
+        }
+    }
+
+    /// The schema of `T`, or a reference to it for named types.
+    pub fn schema<T: JsonSchema + ?Sized>(&mut self) -> Value {
+        self.generator.subschema_for::<T>().to_value()
+    }
+
+    /// The generator collecting the schemas of the document.
+    pub fn generator(&mut self) -> &mut SchemaGenerator {
+        &mut self.generator
+    }
+
+    /// Adds the `operation` object for `method` (e.g. `get`) on `path`.
+    pub fn add_operation(&mut self, method: &str, path: &str, operation: Value) {
+        let item = self
+            .paths
+            .entry(path.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Value::Object(item) = item {
+            item.insert(method.to_string(), operation);
+        }
+    }
+
+    /// Builds the OpenAPI document.
+    pub fn into_document(mut self, title: &str, version: &str) -> Value {
+        json!({
+            "openapi": "3.1.0",
+            "info": {
+                "title": title,
+                "version": version,
+            },
+            "paths": self.paths,
+            "components": {
+                "schemas": self.generator.take_definitions(true),
+            },
+        })
+    }
+}
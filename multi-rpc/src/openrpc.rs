@@ -0,0 +1,130 @@
+//! OpenRPC documents for the JSON-RPC server, generated with the `openrpc` feature.
+//!
+//! `#[multi_rpc_trait]` generates an `openrpc()` function in the trait's protocols module, which
+//! describes every JSON-RPC method. The `jsonrpsee` server factory serves the document through the
+//! `rpc.discover` method. Parameter and result types are described with JSON Schema through
+//! [`schemars`]. Types that don't implement `schemars::JsonSchema` are described by the empty
+//! schema, see [`SchemaMapping`](crate::schema::SchemaMapping). The errors of a method returning
+//! `Result<T, E>` are the codes of `E`, see [`ErrorsMapping`].
+
+use std::marker::PhantomData;
+
+use schemars::generate::SchemaSettings;
+use schemars::JsonSchema;
+use schemars::SchemaGenerator;
+use serde_json::json;
+use serde_json::Value;
+
+use crate::error::ProtocolError;
+
+/// The name of the OpenRPC service discovery method.
+pub const DISCOVER_METHOD: &str = "rpc.discover";
+
+/// Collects the methods and schemas of an OpenRPC document.
+///
+/// Used by the generated `openrpc()` functions. Named types are collected once under
+/// `components/schemas` and referenced from the methods.
+pub struct OpenRpc {
+    generator: SchemaGenerator,
+    methods: Vec<Value>,
+}
+
+impl Default for OpenRpc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OpenRpc {
+    pub fn new() -> Self {
+        let settings = SchemaSettings::draft2020_12().with(|settings| {
+            settings.definitions_path = "/components/schemas".into();
+            settings.meta_schema = None;
+        });
+        Self {
+            generator: settings.into_generator(),
+            methods: Vec::new(),
+        }
+    }
+
+    /// The schema of `T`, or a reference to it for named types.
+    pub fn schema<T: JsonSchema + ?Sized>(&mut self) -> Value {
+        self.generator.subschema_for::<T>().to_value()
+    }
+
+    /// The generator collecting the schemas of the document.
+    pub fn generator(&mut self) -> &mut SchemaGenerator {
+        &mut self.generator
+    }
+
+    /// Adds a `method` object.
+    pub fn add_method(&mut self, method: Value) {
+        self.methods.push(method);
+    }
+
+    /// Builds the OpenRPC document.
+    pub fn into_document(mut self, title: &str, version: &str) -> Value {
+        json!({
+            "openrpc": "1.3.2",
+            "info": {
+                "title": title,
+                "version": version,
+            },
+            "methods": self.methods,
+            "components": {
+                "schemas": self.generator.take_definitions(true),
+            },
+        })
+    }
+}
+
+/// Maps an error type to the `errors` of the methods returning it, through
+/// [`ProtocolError::jsonrpc_errors`] if the type implements `ProtocolError` and to the internal
+/// error otherwise.
+///
+/// Used by the generated documents as `(&ErrorsMapping::<E>::new()).errors()`, with
+/// [`MapProtocolErrors`] and [`MapOtherErrors`] in scope, like
+/// [`SchemaMapping`](crate::schema::SchemaMapping).
+pub struct ErrorsMapping<E>(PhantomData<E>);
+
+impl<E> ErrorsMapping<E> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<E> Default for ErrorsMapping<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The mapping of errors implementing [`ProtocolError`], see [`ErrorsMapping`].
+pub trait MapProtocolErrors {
+    /// The error objects, once per code.
+    fn errors(&self) -> Vec<Value>;
+}
+
+impl<E: ProtocolError> MapProtocolErrors for ErrorsMapping<E> {
+    fn errors(&self) -> Vec<Value> {
+        let mut errors: Vec<Value> = Vec::new();
+        for (code, message) in E::jsonrpc_errors() {
+            if errors.iter().all(|error| error["code"] != code) {
+                errors.push(json!({ "code": code, "message": message }));
+            }
+        }
+        errors
+    }
+}
+
+/// The mapping of all other errors, see [`ErrorsMapping`].
+pub trait MapOtherErrors {
+    /// The internal error object, the only error reported for the type.
+    fn errors(&self) -> Vec<Value>;
+}
+
+impl<E> MapOtherErrors for &ErrorsMapping<E> {
+    fn errors(&self) -> Vec<Value> {
+        vec![json!({ "code": -32603, "message": "Internal error" })]
+    }
+}
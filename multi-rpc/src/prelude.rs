@@ -1,8 +1,23 @@
 //! The multi-rpc prelude for convenient importing of the most common items.
 
+pub use crate::blocking;
 pub use crate::builder::ServerBuilder;
+#[cfg(feature = "tarpc")]
+pub use crate::codec::TarpcCodec;
+#[cfg(feature = "jsonrpsee")]
+pub use crate::config::JsonRpcConfig;
+#[cfg(feature = "jsonrpsee")]
+pub use crate::config::JsonRpcTransports;
+pub use crate::context::Identity;
+pub use crate::context::RequestContext;
 pub use crate::error::RpcError;
+pub use crate::jsonrpc;
 pub use crate::multi_rpc_impl;
 pub use crate::multi_rpc_trait;
 pub use crate::rest;
 pub use crate::runner::ServerRunner;
+#[cfg(feature = "rest-axum")]
+pub use crate::IntoRestResponse;
+pub use crate::RestResponse;
+pub use crate::RpcError;
+pub use crate::Streaming;
@@ -0,0 +1,109 @@
+//! RFC 7807 problem details, the body of the REST server's error responses.
+//!
+//! Every error response of the generated REST endpoints is an `application/problem+json`
+//! [`Problem`]: errors returned by service methods, rejected path, query, body and form input,
+//! and requests to unknown routes.
+//!
+//! ```json
+//! {
+//!   "type": "about:blank",
+//!   "title": "Not Found",
+//!   "status": 404,
+//!   "detail": "item 7 not found",
+//!   "id": 7
+//! }
+//! ```
+//!
+//! The structured data of an error (see [`ProtocolError::data`]) is added as extension members:
+//! the fields of an object, or any other value as `data`.
+//!
+//! [`ProtocolError::data`]: crate::error::ProtocolError::data
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+
+/// The media type of problem details.
+pub const CONTENT_TYPE: &str = "application/problem+json";
+
+/// The problem details of an error response, see the [module docs](self).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct Problem {
+    /// A URI identifying the problem type, `about:blank` if it's described by the status alone.
+    #[serde(rename = "type", default = "about_blank")]
+    pub type_uri: String,
+    /// A short summary of the problem type, the reason phrase of the status for `about:blank`.
+    #[serde(default)]
+    pub title: String,
+    /// The HTTP status of the response.
+    pub status: u16,
+    /// The explanation of this occurrence of the problem, e.g. the error message.
+    #[serde(default)]
+    pub detail: String,
+    /// Additional members describing the problem.
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+fn about_blank() -> String {
+    "about:blank".to_string()
+}
+
+impl Problem {
+    /// A problem of type `about:blank` with the given HTTP `status` and `detail`.
+    pub fn new(status: u16, detail: impl Into<String>) -> Self {
+        let title = http::StatusCode::from_u16(status)
+            .ok()
+            .and_then(|status| status.canonical_reason())
+            .unwrap_or("Error");
+        Self {
+            type_uri: about_blank(),
+            title: title.to_string(),
+            status,
+            detail: detail.into(),
+            extensions: Map::new(),
+        }
+    }
+
+    /// Sets the URI identifying the problem type, and its `title`.
+    pub fn with_type(mut self, type_uri: impl Into<String>, title: impl Into<String>) -> Self {
+        self.type_uri = type_uri.into();
+        self.title = title.into();
+        self
+    }
+
+    /// Adds the structured `data` of an error: the members of an object, which can't replace the
+    /// standard members, or any other value as the `data` member.
+    pub fn with_data(mut self, data: Option<Value>) -> Self {
+        match data {
+            Some(Value::Object(members)) => {
+                for (name, value) in members {
+                    if !matches!(name.as_str(), "type" | "title" | "status" | "detail") {
+                        self.extensions.insert(name, value);
+                    }
+                }
+            }
+            Some(data) => {
+                self.extensions.insert("data".to_string(), data);
+            }
+            None => {}
+        }
+        self
+    }
+}
+
+#[cfg(feature = "rest-axum")]
+impl axum::response::IntoResponse for Problem {
+    fn into_response(self) -> axum::response::Response {
+        let status = http::StatusCode::from_u16(self.status)
+            .unwrap_or(http::StatusCode::INTERNAL_SERVER_ERROR);
+        (
+            status,
+            [(http::header::CONTENT_TYPE, CONTENT_TYPE)],
+            axum::Json(self),
+        )
+            .into_response()
+    }
+}
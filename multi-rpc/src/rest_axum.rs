@@ -0,0 +1,252 @@
+//! Runtime support for the REST handlers generated with the `rest-axum` feature.
+//!
+//! Arguments bound to `header(...)` and `cookie(...)` groups are parsed from the request with
+//! [`FromStr`], and arguments bound to a `multipart(...)` group are read from the
+//! [`MultipartFields`] of the request. Input that can't be parsed, and missing values for
+//! arguments that aren't `Option`s, are rejected with a `400 Bad Request` [`Problem`].
+//!
+//! Return values are sent as JSON, unless they implement [`IntoRestResponse`], see
+//! [`ResponseMapping`], or the route `produces` a raw body, see [`raw_response`].
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use axum::body::Body;
+use axum::body::Bytes;
+use axum::extract::FromRequest;
+use axum::extract::Multipart;
+use axum::extract::Request;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use axum::Router;
+use http::header::CONTENT_TYPE;
+use http::header::COOKIE;
+use http::HeaderMap;
+use http::HeaderValue;
+use http::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::problem::Problem;
+use crate::IntoRestResponse;
+
+/// Answers requests to unknown routes with a `404 Not Found` [`Problem`], and requests with a
+/// method the route doesn't accept with a `405 Method Not Allowed` one.
+///
+/// The generated `rest_axum` servers add these fallbacks to their router. `rest_axum_router()`
+/// comes without them so that it can be merged into other routers; add them to the combined
+/// router instead.
+pub fn problem_fallbacks<S>(router: Router<S>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    router
+        .fallback(|| async { Problem::new(404, "No route matches the request path.") })
+        .method_not_allowed_fallback(|| async {
+            Problem::new(405, "The route doesn't accept the request method.")
+        })
+}
+
+/// Parses the header `name`, if present.
+pub fn header<T>(headers: &HeaderMap, name: &str) -> Result<Option<T>, Problem>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let Some(value) = headers.get(name) else {
+        return Ok(None);
+    };
+    let value = value
+        .to_str()
+        .map_err(|_| Problem::new(400, format!("Header `{}` isn't valid text", name)))?;
+    parse(value, &format!("header `{}`", name)).map(Some)
+}
+
+/// Parses the cookie `name` from the `Cookie` headers, if present.
+pub fn cookie<T>(headers: &HeaderMap, name: &str) -> Result<Option<T>, Problem>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let value = headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(cookie_name, _)| *cookie_name == name)
+        .map(|(_, value)| value.trim_matches('"'));
+    match value {
+        Some(value) => parse(value, &format!("cookie `{}`", name)).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Rejects a missing `value`, described as e.g. ``header `x-api-version` ``.
+pub fn required<T>(value: Option<T>, description: &str) -> Result<T, Problem> {
+    value.ok_or_else(|| Problem::new(400, format!("Missing {}", description)))
+}
+
+/// Extracts `E` from the request body, or `None` for a request without a `Content-Type` header.
+///
+/// Routes whose `body(...)`, `form(...)` or `multipart(...)` fields are all `Option`s accept a
+/// request without a body, and pass `None` for every field.
+pub struct OptionalBody<E>(pub Option<E>);
+
+impl<S, E> FromRequest<S> for OptionalBody<E>
+where
+    S: Send + Sync,
+    E: FromRequest<S>,
+{
+    type Rejection = E::Rejection;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if !request.headers().contains_key(CONTENT_TYPE) {
+            return Ok(Self(None));
+        }
+        E::from_request(request, state)
+            .await
+            .map(|body| Self(Some(body)))
+    }
+}
+
+/// The fields of a `multipart/form-data` request body, by name.
+///
+/// If a field is sent more than once, the last one is kept.
+#[derive(Default)]
+pub struct MultipartFields {
+    fields: HashMap<String, Bytes>,
+}
+
+impl MultipartFields {
+    /// Reads every field of `multipart` into memory.
+    pub async fn read(mut multipart: Multipart) -> Result<Self, Problem> {
+        let mut fields = HashMap::new();
+        loop {
+            let field = multipart
+                .next_field()
+                .await
+                .map_err(|err| Problem::new(err.status().as_u16(), err.body_text()))?;
+            let Some(field) = field else {
+                return Ok(Self { fields });
+            };
+            let name = field.name().unwrap_or_default().to_string();
+            let contents = field
+                .bytes()
+                .await
+                .map_err(|err| Problem::new(err.status().as_u16(), err.body_text()))?;
+            fields.insert(name, contents);
+        }
+    }
+
+    /// The contents of the file field `name`, if present, e.g. as a `Vec<u8>` or `bytes::Bytes`.
+    pub fn file<T: From<Vec<u8>>>(&self, name: &str) -> Result<Option<T>, Problem> {
+        Ok(self
+            .fields
+            .get(name)
+            .map(|contents| T::from(contents.to_vec())))
+    }
+
+    /// Deserializes the text field `name`, if present.
+    ///
+    /// The text is deserialized as a string, or else parsed as JSON, so that `"42"` is accepted
+    /// by both `String` and `u32` arguments.
+    pub fn text<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>, Problem> {
+        let Some(contents) = self.fields.get(name) else {
+            return Ok(None);
+        };
+        let invalid = |err: &dyn Display| {
+            Problem::new(400, format!("Invalid multipart field `{}`: {}", name, err))
+        };
+        let text = std::str::from_utf8(contents).map_err(|err| invalid(&err))?;
+        serde_json::from_value(serde_json::Value::String(text.to_string()))
+            .or_else(|_| serde_json::from_str(text))
+            .map(Some)
+            .map_err(|err| invalid(&err))
+    }
+}
+
+/// Responds with any return value, through [`IntoRestResponse`] if the value implements it and as
+/// JSON otherwise.
+///
+/// Used by the generated handlers as `ResponseMapping(value).into_rest_response(status)`, with
+/// [`MapIntoRestResponse`] and [`MapJsonResponse`] in scope. Method resolution prefers the
+/// `MapIntoRestResponse` impl, which only applies if the value implements `IntoRestResponse`.
+pub struct ResponseMapping<T>(pub T);
+
+/// The response of values implementing [`IntoRestResponse`], see [`ResponseMapping`].
+pub trait MapIntoRestResponse {
+    fn into_rest_response(self, status: StatusCode) -> Response;
+}
+
+impl<T: IntoRestResponse> MapIntoRestResponse for ResponseMapping<T> {
+    fn into_rest_response(self, status: StatusCode) -> Response {
+        self.0.into_rest_response(status)
+    }
+}
+
+/// The JSON response of all other values, see [`ResponseMapping`].
+pub trait MapJsonResponse {
+    fn into_rest_response(self, status: StatusCode) -> Response;
+}
+
+impl<T: Serialize> MapJsonResponse for &ResponseMapping<T> {
+    fn into_rest_response(self, status: StatusCode) -> Response {
+        json_response(status, &self.0)
+    }
+}
+
+/// The JSON response of `value`, without a body for a status that doesn't allow one, i.e.
+/// `204 No Content` and `304 Not Modified`.
+pub fn json_response<T: Serialize>(status: StatusCode, value: T) -> Response {
+    if !allows_body(status) {
+        return status.into_response();
+    }
+    (status, axum::Json(value)).into_response()
+}
+
+/// Sets the `Content-Type` of a JSON `response` to `content_type`, the JSON media type a route
+/// `produces`, e.g. `application/vnd.api+json`. Responses of another type, such as those of
+/// [`IntoRestResponse`] values or responses without a body, are left untouched.
+pub fn with_json_content_type(mut response: Response, content_type: &'static str) -> Response {
+    if response
+        .headers()
+        .get(CONTENT_TYPE)
+        .is_some_and(|value| value == "application/json")
+    {
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    }
+    response
+}
+
+/// The response of a route that `produces` a raw body of type `content_type`, e.g. a `String` or
+/// `Vec<u8>`, without a body for a status that doesn't allow one.
+pub fn raw_response(
+    status: StatusCode,
+    content_type: &'static str,
+    body: impl Into<Body>,
+) -> Response {
+    if !allows_body(status) {
+        return status.into_response();
+    }
+    (status, [(CONTENT_TYPE, content_type)], body.into()).into_response()
+}
+
+// Whether a response with `status` may have a body, i.e. unless it's `204 No Content` or
+// `304 Not Modified`.
+pub(crate) fn allows_body(status: StatusCode) -> bool {
+    status != StatusCode::NO_CONTENT && status != StatusCode::NOT_MODIFIED
+}
+
+fn parse<T>(value: &str, description: &str) -> Result<T, Problem>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value
+        .parse()
+        .map_err(|err| Problem::new(400, format!("Invalid {}: {}", description, err)))
+}
@@ -0,0 +1,196 @@
+use http::header;
+use http::HeaderMap;
+use http::HeaderName;
+use http::HeaderValue;
+use http::StatusCode;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+
+/// A return value with the status and headers of its REST response.
+///
+/// Over REST, the value is the JSON body of a response with the status and headers set here.
+/// Over tarpc and JSON-RPC, only the value is sent: `RestResponse<T>` serializes exactly like `T`.
+///
+/// ```rust,ignore
+/// #[rest(method = POST, path = "/users", body(name))]
+/// async fn create_user(&self, name: String) -> RestResponse<User> {
+///     let user = self.store.insert(name);
+///     RestResponse::created(format!("/users/{}", user.id), user)
+///         .with_cache_control("no-store")
+/// }
+/// ```
+///
+/// Without a status, the response has the status of the route, `200 OK` unless the `#[rest]`
+/// attribute sets `status = ...`.
+#[derive(Clone, Debug)]
+pub struct RestResponse<T> {
+    value: T,
+    status: Option<StatusCode>,
+    headers: HeaderMap,
+}
+
+impl<T> RestResponse<T> {
+    /// Wraps `value` to be returned from a service method.
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            status: None,
+            headers: HeaderMap::new(),
+        }
+    }
+
+    /// A `201 Created` response for the resource at `location`.
+    pub fn created(location: impl AsRef<str>, value: T) -> Self {
+        Self::new(value)
+            .with_status(StatusCode::CREATED)
+            .with_location(location)
+    }
+
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Sets the `Location` header, ignored if `location` isn't a valid header value.
+    pub fn with_location(self, location: impl AsRef<str>) -> Self {
+        self.with_header(header::LOCATION, location)
+    }
+
+    /// Sets the `Cache-Control` header, e.g. to `max-age=60`, ignored if `cache_control` isn't a
+    /// valid header value.
+    pub fn with_cache_control(self, cache_control: impl AsRef<str>) -> Self {
+        self.with_header(header::CACHE_CONTROL, cache_control)
+    }
+
+    /// Sets the header `name`, ignored if `value` isn't a valid header value.
+    pub fn with_header(mut self, name: HeaderName, value: impl AsRef<str>) -> Self {
+        if let Ok(value) = HeaderValue::from_str(value.as_ref()) {
+            self.headers.insert(name, value);
+        }
+        self
+    }
+
+    /// The status of the REST response, if set.
+    pub fn status(&self) -> Option<StatusCode> {
+        self.status
+    }
+
+    /// The headers of the REST response.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// The REST response, with the status of the route unless the status is set.
+    #[cfg(feature = "rest-axum")]
+    pub fn into_rest_axum_response(self, route_status: StatusCode) -> axum::response::Response
+    where
+        T: Serialize,
+    {
+        use axum::response::IntoResponse;
+
+        let status = self.status.unwrap_or(route_status);
+        let response = crate::rest_axum::json_response(status, self.value);
+        (self.headers, response).into_response()
+    }
+
+    /// The REST response with the value as its raw body, of type `content_type` unless the
+    /// `Content-Type` header is set. A status that doesn't allow a body, `204 No Content` or
+    /// `304 Not Modified`, is sent without the value.
+    #[cfg(feature = "rest-axum")]
+    pub fn into_rest_axum_raw_response(
+        mut self,
+        route_status: StatusCode,
+        content_type: &'static str,
+    ) -> axum::response::Response
+    where
+        T: Into<axum::body::Body>,
+    {
+        use axum::response::IntoResponse;
+
+        let status = self.status.unwrap_or(route_status);
+        if !crate::rest_axum::allows_body(status) {
+            return (status, self.headers).into_response();
+        }
+        self.headers
+            .entry(header::CONTENT_TYPE)
+            .or_insert(HeaderValue::from_static(content_type));
+        (status, self.headers, self.value.into()).into_response()
+    }
+}
+
+/// A return value that builds its own REST response, e.g. to send a body that isn't JSON.
+///
+/// The REST handler of a method returning a type implementing this trait responds with
+/// [`into_rest_response`](Self::into_rest_response), and values of other types are sent as JSON.
+/// Over tarpc and JSON-RPC, the value is serialized as usual.
+///
+/// ```rust,ignore
+/// #[derive(Serialize, Deserialize)]
+/// struct Csv(String);
+///
+/// impl IntoRestResponse for Csv {
+///     fn into_rest_response(self, status: StatusCode) -> axum::response::Response {
+///         (status, [(header::CONTENT_TYPE, "text/csv")], self.0).into_response()
+///     }
+/// }
+/// ```
+#[cfg(feature = "rest-axum")]
+pub trait IntoRestResponse {
+    /// The REST response, where `status` is the status of the route.
+    fn into_rest_response(self, status: StatusCode) -> axum::response::Response;
+}
+
+#[cfg(feature = "rest-axum")]
+impl<T: Serialize> IntoRestResponse for RestResponse<T> {
+    fn into_rest_response(self, status: StatusCode) -> axum::response::Response {
+        self.into_rest_axum_response(status)
+    }
+}
+
+impl<T> From<T> for RestResponse<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: Serialize> Serialize for RestResponse<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for RestResponse<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+// Described by the schema of the value, which is all that's serialized.
+#[cfg(any(feature = "openapi", feature = "openrpc"))]
+impl<T: schemars::JsonSchema> schemars::JsonSchema for RestResponse<T> {
+    fn inline_schema() -> bool {
+        T::inline_schema()
+    }
+
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        T::schema_name()
+    }
+
+    fn schema_id() -> std::borrow::Cow<'static, str> {
+        T::schema_id()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        T::json_schema(generator)
+    }
+}
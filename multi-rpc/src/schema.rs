@@ -0,0 +1,56 @@
+//! JSON Schemas of the types described by the OpenAPI and OpenRPC documents.
+//!
+//! Types implementing `schemars::JsonSchema` are described by their schema. Any other type is
+//! described by the empty schema `{}`, which accepts every value, so enabling the `openapi` or
+//! `openrpc` feature never breaks a service whose types don't implement it.
+
+use std::marker::PhantomData;
+
+use schemars::JsonSchema;
+use schemars::SchemaGenerator;
+use serde_json::Map;
+use serde_json::Value;
+
+/// Maps a type to its JSON Schema, through `JsonSchema` if the type implements it and to the
+/// empty schema otherwise.
+///
+/// Used by the generated documents as `(&SchemaMapping::<T>::new()).schema(generator)`, with
+/// [`MapJsonSchema`] and [`MapOtherSchema`] in scope. Method resolution prefers the
+/// `MapJsonSchema` impl, which only applies if `T` implements `JsonSchema`.
+pub struct SchemaMapping<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized> SchemaMapping<T> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: ?Sized> Default for SchemaMapping<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The mapping of types implementing `JsonSchema`, see [`SchemaMapping`].
+pub trait MapJsonSchema {
+    /// The schema of the type, or a reference to it for named types.
+    fn schema(&self, generator: &mut SchemaGenerator) -> Value;
+}
+
+impl<T: JsonSchema + ?Sized> MapJsonSchema for SchemaMapping<T> {
+    fn schema(&self, generator: &mut SchemaGenerator) -> Value {
+        generator.subschema_for::<T>().to_value()
+    }
+}
+
+/// The mapping of all other types, see [`SchemaMapping`].
+pub trait MapOtherSchema {
+    /// The empty schema.
+    fn schema(&self, generator: &mut SchemaGenerator) -> Value;
+}
+
+impl<T: ?Sized> MapOtherSchema for &SchemaMapping<T> {
+    fn schema(&self, _generator: &mut SchemaGenerator) -> Value {
+        Value::Object(Map::new())
+    }
+}
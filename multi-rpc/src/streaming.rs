@@ -0,0 +1,34 @@
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+
+use futures::Stream;
+
+/// The return type of a server-streaming service method.
+///
+/// A method returning `Streaming<T>` is served as a subscription over JSON-RPC, as a
+/// `text/event-stream` (Server-Sent Events) endpoint over REST, and as a session that the client
+/// reads chunk by chunk over tarpc, see `multi_rpc::tarpc_streaming`.
+///
+/// The stream must be `'static`: it keeps running after the method has returned, so clone
+/// whatever state it needs into it.
+pub struct Streaming<T>(Pin<Box<dyn Stream<Item = T> + Send>>);
+
+impl<T> Streaming<T> {
+    /// Wraps `stream` to be returned from a service method.
+    pub fn new(stream: impl Stream<Item = T> + Send + 'static) -> Self {
+        Self(Box::pin(stream))
+    }
+}
+
+impl<T> Stream for Streaming<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.0.as_mut().poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
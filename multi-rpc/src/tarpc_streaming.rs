@@ -0,0 +1,190 @@
+//! Server-streaming methods over tarpc.
+//!
+//! tarpc answers every request with a single response, so a method returning `Streaming<T>` is
+//! served as a session of three tarpc methods. For `ticks`, `open_ticks` calls the service method
+//! and returns the id of the session holding its stream, `next_ticks` returns the next
+//! [`Chunk`] of items, and `cancel_ticks` drops the stream early. Sessions belong to their
+//! connection and are dropped with it.
+//!
+//! The generated tarpc client wraps the session in an inherent `ticks` method that returns the
+//! items as a stream, see [`receive`].
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use futures::FutureExt;
+use futures::StreamExt;
+use serde::Deserialize;
+use serde::Serialize;
+use tarpc::client::RpcError;
+use tokio::sync::Notify;
+
+use crate::streaming::Streaming;
+
+/// Identifies a stream opened on a connection.
+pub type SessionId = u64;
+
+// The most items sent in a single chunk.
+const MAX_CHUNK_ITEMS: usize = 256;
+
+/// The items of a stream that were produced since the previous chunk.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Chunk<T> {
+    pub items: Vec<T>,
+    /// Whether the stream has ended, after `items`.
+    pub end: bool,
+}
+
+/// The streams opened on a tarpc connection, by session.
+#[derive(Clone, Default)]
+pub struct Sessions(Arc<Mutex<SessionsInner>>);
+
+#[derive(Default)]
+struct SessionsInner {
+    next_id: SessionId,
+    sessions: HashMap<SessionId, Session>,
+}
+
+// A stream stays in its session while `next` waits for its items, so that a `cancel` arriving in
+// the meantime removes it and wakes the waiting `next`.
+struct Session {
+    // `Streaming<T>` of the item type of the method that opened the session.
+    stream: Arc<tokio::sync::Mutex<Box<dyn Any + Send>>>,
+    cancelled: Arc<Notify>,
+}
+
+impl Sessions {
+    /// Stores `stream` until it ends or is cancelled, returning its session.
+    pub fn open<T: 'static>(&self, stream: Streaming<T>) -> SessionId {
+        let mut inner = self.0.lock().unwrap();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        let session = Session {
+            stream: Arc::new(tokio::sync::Mutex::new(Box::new(stream))),
+            cancelled: Arc::new(Notify::new()),
+        };
+        inner.sessions.insert(id, session);
+        id
+    }
+
+    /// Waits for the next items of the stream of `session`, returning early with no items when
+    /// half the time to `deadline` has passed, so that the response arrives in time.
+    ///
+    /// An unknown session, e.g. one that has ended or was cancelled, answers the end of the
+    /// stream, and so does a session cancelled while waiting.
+    pub async fn next<T: 'static>(&self, session: SessionId, deadline: Instant) -> Chunk<T> {
+        let end = Chunk {
+            items: Vec::new(),
+            end: true,
+        };
+        let Some((stream, cancelled)) = self
+            .0
+            .lock()
+            .unwrap()
+            .sessions
+            .get(&session)
+            .map(|entry| (entry.stream.clone(), entry.cancelled.clone()))
+        else {
+            return end;
+        };
+        let mut stream = stream.lock().await;
+        // Another `next` may have held the stream until the session was cancelled.
+        if !self.0.lock().unwrap().sessions.contains_key(&session) {
+            return end;
+        }
+        let Some(stream) = stream.downcast_mut::<Streaming<T>>() else {
+            self.cancel(session);
+            return end;
+        };
+
+        let wait = deadline.saturating_duration_since(Instant::now()) / 2;
+        let first = tokio::select! {
+            first = tokio::time::timeout(wait, stream.next()) => first,
+            // The stream is dropped with the last handle to it, when this call returns.
+            () = cancelled.notified() => return end,
+        };
+        let mut chunk = match first {
+            Ok(Some(item)) => Chunk {
+                items: vec![item],
+                end: false,
+            },
+            Ok(None) => end,
+            Err(_) => Chunk {
+                items: Vec::new(),
+                end: false,
+            },
+        };
+        while !chunk.end && !chunk.items.is_empty() && chunk.items.len() < MAX_CHUNK_ITEMS {
+            match stream.next().now_or_never() {
+                Some(Some(item)) => chunk.items.push(item),
+                Some(None) => chunk.end = true,
+                None => break,
+            }
+        }
+
+        if chunk.end {
+            self.cancel(session);
+        }
+        chunk
+    }
+
+    /// Drops the stream of `session`, or, while a `next` waits for its items, makes that `next`
+    /// answer the end of the stream and drop it.
+    pub fn cancel(&self, session: SessionId) {
+        if let Some(session) = self.0.lock().unwrap().sessions.remove(&session) {
+            session.cancelled.notify_one();
+        }
+    }
+}
+
+/// Receives the items of a session chunk by chunk with `next`, calling `cancel` if the returned
+/// stream is dropped before the end.
+///
+/// A failed `next` call ends the stream with its error.
+pub fn receive<T, N, F>(
+    next: N,
+    cancel: impl FnOnce() + Send + 'static,
+) -> Streaming<Result<T, RpcError>>
+where
+    T: Send + 'static,
+    N: FnMut() -> F + Send + 'static,
+    F: Future<Output = Result<Chunk<T>, RpcError>> + Send,
+{
+    let state = (next, CancelOnDrop(Some(Box::new(cancel))));
+    let chunks = futures::stream::unfold(Some(state), |state| async move {
+        let (mut next, mut cancel) = state?;
+        match next().await {
+            Ok(chunk) if chunk.end => {
+                cancel.0 = None;
+                Some((Ok(chunk.items), None))
+            }
+            Ok(chunk) => Some((Ok(chunk.items), Some((next, cancel)))),
+            Err(err) => {
+                cancel.0 = None;
+                Some((Err(err), None))
+            }
+        }
+    });
+    Streaming::new(chunks.flat_map(|chunk| {
+        let items: Vec<_> = match chunk {
+            Ok(items) => items.into_iter().map(Ok).collect(),
+            Err(err) => vec![Err(err)],
+        };
+        futures::stream::iter(items)
+    }))
+}
+
+// Cancels the session when the stream is dropped before its end.
+struct CancelOnDrop(Option<Box<dyn FnOnce() + Send>>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if let Some(cancel) = self.0.take() {
+            cancel();
+        }
+    }
+}
@@ -0,0 +1 @@
+//! Compile tests for `multi-rpc` built with only the `rest-axum` protocol feature.
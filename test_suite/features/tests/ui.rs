@@ -0,0 +1,8 @@
+//! Compile errors for protocols whose cargo feature is disabled, checked against the `.stderr`
+//! files next to the cases.
+
+#[test]
+fn disabled_features() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,18 @@
+//! Tests of the code generated by `multi-rpc`, kept in their own crate so that they can enable
+//! every feature of `multi-rpc`. The tests are in the `tests` directory.
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use tokio::net::TcpStream;
+
+/// Waits until a server spawned in the background accepts connections on `addr`.
+pub async fn wait_for_server(addr: SocketAddr) {
+    for _ in 0..100 {
+        if TcpStream::connect(addr).await.is_ok() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("no server is listening on {addr}");
+}
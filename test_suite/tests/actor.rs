@@ -0,0 +1,70 @@
+//! Services implemented with `#[multi_rpc_impl(actor)]`.
+
+use axum::body::Body;
+use axum::http::Request;
+use axum::http::StatusCode;
+use multi_rpc::prelude::*;
+use multi_rpc::problem::Problem;
+use tower::ServiceExt;
+
+#[multi_rpc_trait(protocols(rest_axum))]
+pub trait Counter {
+    fn add(&mut self, amount: u64) -> u64;
+}
+
+pub struct MyCounter(u64);
+
+#[multi_rpc_impl(protocols(rest_axum), actor)]
+impl Counter for MyCounter {
+    #[blocking]
+    #[rest(method = POST, path = "/add/{amount}")]
+    fn add(&mut self, amount: u64) -> u64 {
+        self.0 += amount;
+        self.0
+    }
+}
+
+async fn add(router: &axum::Router, amount: u64) -> (StatusCode, axum::body::Bytes) {
+    let request = Request::post(format!("/add/{amount}"))
+        .body(Body::empty())
+        .unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, body)
+}
+
+// `#[tokio::test]` runs on the current-thread runtime.
+#[tokio::test]
+async fn blocking_methods_keep_the_service_state() {
+    let builder = ServerBuilder::actor(MyCounter(0), 8);
+    let router =
+        counter_for_my_counter_impls::rest_axum_router().with_state(builder.service().clone());
+    let _runner = builder.build().unwrap();
+
+    assert_eq!(add(&router, 2).await, (StatusCode::OK, "2".into()));
+    assert_eq!(add(&router, 3).await, (StatusCode::OK, "5".into()));
+}
+
+#[tokio::test]
+async fn calls_to_a_stopped_actor_fail_with_503() {
+    let builder = ServerBuilder::actor(MyCounter(0), 8);
+    let router =
+        counter_for_my_counter_impls::rest_axum_router().with_state(builder.service().clone());
+    // The actor task is never spawned.
+    drop(builder);
+
+    let (status, body) = add(&router, 2).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    let problem: Problem = serde_json::from_slice(&body).unwrap();
+    assert_eq!(problem.detail, "the service has stopped");
+}
+
+#[tokio::test]
+async fn zero_queue_capacity_is_rejected() {
+    let builder = ServerBuilder::actor(MyCounter(0), 0);
+    let err = builder.build().err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
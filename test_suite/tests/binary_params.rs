@@ -0,0 +1,133 @@
+//! `Vec<u8>` arguments: multipart uploads over REST, and arrays or base64 strings over JSON-RPC.
+
+use std::sync::Arc;
+
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::ClientError;
+use jsonrpsee::http_client::HttpClientBuilder;
+use jsonrpsee::rpc_params;
+use multi_rpc::prelude::*;
+use multi_rpc_test_suite::wait_for_server;
+use tokio::sync::RwLock;
+
+#[multi_rpc_trait(protocols(rest_axum, jsonrpsee))]
+#[allow(async_fn_in_trait)]
+pub trait Files {
+    async fn upload(&self, name: String, file: Vec<u8>) -> String;
+    async fn checksum(&self, data: Vec<u8>) -> u32;
+}
+
+pub struct MyFiles;
+
+#[multi_rpc_impl(protocols(rest_axum, jsonrpsee))]
+impl Files for MyFiles {
+    #[rest(method = POST, path = "/files", multipart(name, file))]
+    async fn upload(&self, name: String, file: Vec<u8>) -> String {
+        format!("{name}: {}", String::from_utf8_lossy(&file))
+    }
+
+    #[rest(method = POST, path = "/checksum", body(data))]
+    async fn checksum(&self, data: Vec<u8>) -> u32 {
+        data.iter().map(|&byte| u32::from(byte)).sum()
+    }
+}
+
+fn service() -> Arc<RwLock<MyFiles>> {
+    Arc::new(RwLock::new(MyFiles))
+}
+
+#[tokio::test]
+async fn rest_uploads_multipart_files() {
+    let addr = ([127, 0, 0, 1], 39231).into();
+    tokio::spawn(files_for_my_files_impls::rest_axum(addr)(service()));
+    wait_for_server(addr).await;
+
+    let form = reqwest::multipart::Form::new().text("name", "report").part(
+        "file",
+        reqwest::multipart::Part::bytes(b"hello".to_vec()).file_name("report.txt"),
+    );
+    let response = reqwest::Client::new()
+        .post(format!("http://{}/files", addr))
+        .multipart(form)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.json::<String>().await.unwrap(), "report: hello");
+
+    let client = files_for_my_files_impls::FilesRestClient::new(format!("http://{}", addr));
+    let uploaded = client
+        .upload("report".to_string(), b"hello".to_vec())
+        .await
+        .unwrap();
+    assert_eq!(uploaded, "report: hello");
+}
+
+#[tokio::test]
+async fn jsonrpc_accepts_base64_for_multipart_arguments() {
+    let addr = ([127, 0, 0, 1], 39232).into();
+    tokio::spawn(files_for_my_files_impls::jsonrpsee(addr)(service()));
+    wait_for_server(addr).await;
+    let client = HttpClientBuilder::default()
+        .build(format!("http://{}", addr))
+        .unwrap();
+
+    // "aGVsbG8=" is "hello" in base64.
+    let uploaded: String = client
+        .request("upload", rpc_params!["report", "aGVsbG8="])
+        .await
+        .unwrap();
+    assert_eq!(uploaded, "report: hello");
+    let uploaded: String = client
+        .request("upload", rpc_params!["report", [104, 101, 108, 108, 111]])
+        .await
+        .unwrap();
+    assert_eq!(uploaded, "report: hello");
+
+    match client
+        .request::<String, _>("upload", rpc_params!["report", "not base64!"])
+        .await
+    {
+        Err(ClientError::Call(err)) => {
+            assert_eq!(err.code(), -32602);
+            assert!(
+                err.message().starts_with("invalid base64"),
+                "{}",
+                err.message()
+            );
+        }
+        other => panic!("expected invalid params, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn jsonrpc_takes_other_binary_arguments_as_arrays() {
+    let addr = ([127, 0, 0, 1], 39233).into();
+    tokio::spawn(files_for_my_files_impls::jsonrpsee(addr)(service()));
+    wait_for_server(addr).await;
+    let client = HttpClientBuilder::default()
+        .build(format!("http://{}", addr))
+        .unwrap();
+
+    let checksum: u32 = client
+        .request("checksum", rpc_params![[1, 2, 3]])
+        .await
+        .unwrap();
+    assert_eq!(checksum, 6);
+
+    // Only the arguments bound to `multipart(...)` are decoded from base64.
+    match client
+        .request::<u32, _>("checksum", rpc_params!["AQID"])
+        .await
+    {
+        Err(ClientError::Call(err)) => assert_eq!(err.code(), -32602),
+        other => panic!("expected invalid params, got {:?}", other),
+    }
+
+    // Arrays are accepted for every binary argument, which is what OpenRPC describes.
+    let document = files_protocols::openrpc();
+    for method in document["methods"].as_array().unwrap() {
+        let schema = &method["params"].as_array().unwrap().last().unwrap()["schema"];
+        assert_eq!(schema["type"], "array", "{method}");
+    }
+}
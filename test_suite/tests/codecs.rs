@@ -0,0 +1,143 @@
+//! Round trips over tarpc with every codec, between `tarpc_tcp_with_codec` and `connect_tarpc`.
+
+use std::sync::Arc;
+
+use futures::StreamExt;
+use multi_rpc::prelude::*;
+use multi_rpc_test_suite::wait_for_server;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::sync::RwLock;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Shape {
+    Circle { radius: f64 },
+    Square(u32),
+    Empty,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Drawing {
+    pub name: String,
+    pub shapes: Vec<Shape>,
+    pub note: Option<String>,
+    pub bytes: Vec<u8>,
+}
+
+#[multi_rpc_trait(protocols(tarpc))]
+#[allow(async_fn_in_trait)]
+pub trait Canvas {
+    async fn echo(&self, drawing: Drawing) -> Drawing;
+    async fn find(&self, name: String) -> Result<Drawing, RpcError>;
+    async fn sizes(&self, count: u32) -> Streaming<u32>;
+}
+
+pub struct MyCanvas;
+
+#[multi_rpc_impl(protocols(tarpc))]
+impl Canvas for MyCanvas {
+    async fn echo(&self, drawing: Drawing) -> Drawing {
+        drawing
+    }
+
+    async fn find(&self, name: String) -> Result<Drawing, RpcError> {
+        Err(RpcError::NotFound(name))
+    }
+
+    async fn sizes(&self, count: u32) -> Streaming<u32> {
+        Streaming::new(futures::stream::iter(0..count))
+    }
+}
+
+// Serves `MyCanvas` on `port` with `codec`, and calls every method with a client using it.
+async fn round_trip(port: u16, codec: TarpcCodec) {
+    let addr = ([127, 0, 0, 1], port).into();
+    let service = Arc::new(RwLock::new(MyCanvas));
+    tokio::spawn(canvas_for_my_canvas_impls::tarpc_tcp_with_codec(
+        addr, codec,
+    )(service));
+    wait_for_server(addr).await;
+
+    let client = canvas_protocols::connect_tarpc(addr, codec).await.unwrap();
+    let drawing = Drawing {
+        name: "house".to_string(),
+        shapes: vec![
+            Shape::Circle { radius: 1.5 },
+            Shape::Square(4),
+            Shape::Empty,
+        ],
+        note: None,
+        bytes: vec![0, 1, 254, 255],
+    };
+    let echoed = client
+        .echo(tarpc::context::current(), drawing.clone())
+        .await
+        .unwrap();
+    assert_eq!(echoed, drawing, "{codec:?}");
+
+    match client
+        .find(tarpc::context::current(), "house".to_string())
+        .await
+        .unwrap()
+    {
+        Err(RpcError::NotFound(name)) => assert_eq!(name, "house", "{codec:?}"),
+        other => panic!("expected a not found error with {codec:?}, got {other:?}"),
+    }
+
+    let sizes: Vec<u32> = client
+        .sizes(tarpc::context::current(), 300)
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .collect()
+        .await;
+    assert_eq!(sizes, (0..300).collect::<Vec<_>>(), "{codec:?}");
+}
+
+#[tokio::test]
+async fn json() {
+    round_trip(39181, TarpcCodec::Json).await;
+}
+
+#[tokio::test]
+async fn bincode() {
+    round_trip(39182, TarpcCodec::Bincode).await;
+}
+
+#[cfg(feature = "tarpc-messagepack")]
+#[tokio::test]
+async fn messagepack() {
+    round_trip(39183, TarpcCodec::MessagePack).await;
+}
+
+#[cfg(feature = "tarpc-cbor")]
+#[tokio::test]
+async fn cbor() {
+    round_trip(39184, TarpcCodec::Cbor).await;
+}
+
+#[tokio::test]
+async fn clients_must_use_the_codec_of_the_server() {
+    let addr = ([127, 0, 0, 1], 39185).into();
+    let service = Arc::new(RwLock::new(MyCanvas));
+    tokio::spawn(canvas_for_my_canvas_impls::tarpc_tcp_with_codec(
+        addr,
+        TarpcCodec::Bincode,
+    )(service));
+    wait_for_server(addr).await;
+
+    let client = canvas_protocols::connect_tarpc(addr, TarpcCodec::Json)
+        .await
+        .unwrap();
+    assert!(client.sizes(tarpc::context::current(), 1).await.is_err());
+}
+
+#[tokio::test]
+async fn servers_end_when_their_address_is_taken() {
+    let addr = ([127, 0, 0, 1], 39186).into();
+    let _taken = std::net::TcpListener::bind(addr).unwrap();
+    let service = Arc::new(RwLock::new(MyCanvas));
+    let server = tokio::spawn(canvas_for_my_canvas_impls::tarpc_tcp(addr)(service));
+    let ended = tokio::time::timeout(std::time::Duration::from_secs(5), server).await;
+    assert!(matches!(ended, Ok(Ok(()))), "{ended:?}");
+}
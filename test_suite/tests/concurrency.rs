@@ -0,0 +1,91 @@
+//! The lock mode of the shared service follows the receiver of each method.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::http::Request;
+use multi_rpc::prelude::*;
+use tokio::sync::Barrier;
+use tokio::sync::RwLock;
+use tower::ServiceExt;
+
+#[multi_rpc_trait(protocols(rest_axum))]
+#[allow(async_fn_in_trait)]
+pub trait Store {
+    async fn meet(&self);
+    async fn is_writing(&self) -> bool;
+    async fn write(&mut self);
+}
+
+pub struct MyStore {
+    barrier: Barrier,
+    writing: bool,
+}
+
+#[multi_rpc_impl(protocols(rest_axum))]
+impl Store for MyStore {
+    // Only returns once two calls are running at the same time.
+    #[rest(method = POST, path = "/meet")]
+    async fn meet(&self) {
+        self.barrier.wait().await;
+    }
+
+    #[rest(method = GET, path = "/writing")]
+    async fn is_writing(&self) -> bool {
+        self.writing
+    }
+
+    #[rest(method = POST, path = "/write")]
+    async fn write(&mut self) {
+        self.writing = true;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        self.writing = false;
+    }
+}
+
+fn router() -> axum::Router {
+    store_for_my_store_impls::rest_axum_router().with_state(Arc::new(RwLock::new(MyStore {
+        barrier: Barrier::new(2),
+        writing: false,
+    })))
+}
+
+async fn call(router: &axum::Router, method: &str, uri: &str) -> String {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .body(Body::empty())
+        .unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert!(response.status().is_success(), "{method} {uri}");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn shared_receivers_run_concurrently() {
+    let router = router();
+    let both = futures::future::join(
+        call(&router, "POST", "/meet"),
+        call(&router, "POST", "/meet"),
+    );
+    tokio::time::timeout(Duration::from_secs(5), both)
+        .await
+        .expect("`&self` calls were serialized");
+}
+
+#[tokio::test]
+async fn mutable_receivers_run_exclusively() {
+    let router = router();
+    let write = call(&router, "POST", "/write");
+    let read = async {
+        // Starts once the write is in progress, and must wait for it to finish.
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        call(&router, "GET", "/writing").await
+    };
+    let (_, writing) = futures::future::join(write, read).await;
+    assert_eq!(writing, "false");
+}
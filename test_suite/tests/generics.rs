@@ -0,0 +1,53 @@
+//! Services that are generic over their implementation, e.g. a storage backend.
+
+use std::sync::Arc;
+
+use multi_rpc::prelude::*;
+use multi_rpc_test_suite::wait_for_server;
+use tokio::sync::RwLock;
+
+pub trait Backend: Send + Sync + 'static {
+    fn name(&self) -> String;
+}
+
+pub struct Memory;
+
+impl Backend for Memory {
+    fn name(&self) -> String {
+        "memory".to_string()
+    }
+}
+
+#[multi_rpc_trait]
+#[allow(async_fn_in_trait)]
+pub trait Greeter {
+    async fn greet(&self, name: String) -> String;
+}
+
+pub struct Service<T>(T);
+
+// `T` must not collide with the generic parameters added by the generated code.
+#[multi_rpc_impl]
+impl<T: Backend> Greeter for Service<T> {
+    #[rest(method = GET, path = "/greet/{name}")]
+    async fn greet(&self, name: String) -> String {
+        format!("Hello, {}! Stored in {}.", name, self.0.name())
+    }
+}
+
+#[tokio::test]
+async fn generic_service_is_served_over_tarpc() {
+    let addr = ([127, 0, 0, 1], 39101).into();
+    let service = Arc::new(RwLock::new(Service(Memory)));
+    tokio::spawn(greeter_for_service_impls::tarpc_tcp(addr)(service));
+    wait_for_server(addr).await;
+
+    let client = greeter_protocols::connect_tarpc(addr, multi_rpc::codec::TarpcCodec::Json)
+        .await
+        .unwrap();
+    let greeting = client
+        .greet(tarpc::context::current(), "Ada".to_string())
+        .await
+        .unwrap();
+    assert_eq!(greeting, "Hello, Ada! Stored in memory.");
+}
@@ -0,0 +1,137 @@
+//! Arguments bound to `header(...)` and `cookie(...)` groups.
+
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::http::Request;
+use axum::http::StatusCode;
+use multi_rpc::prelude::*;
+use multi_rpc_test_suite::wait_for_server;
+use serde_json::Value;
+use tokio::sync::RwLock;
+use tower::ServiceExt;
+
+#[multi_rpc_trait(protocols(rest_axum))]
+#[allow(async_fn_in_trait)]
+pub trait Account {
+    async fn profile(
+        &self,
+        id: u32,
+        api_version: u32,
+        session_id: String,
+        locale: Option<String>,
+    ) -> String;
+}
+
+pub struct MyAccount;
+
+#[multi_rpc_impl(protocols(rest_axum))]
+impl Account for MyAccount {
+    #[rest(
+        method = GET,
+        path = "/profiles/{id}",
+        header(api_version = "x-api-version", locale),
+        cookie(session_id = "SID")
+    )]
+    async fn profile(
+        &self,
+        id: u32,
+        api_version: u32,
+        session_id: String,
+        locale: Option<String>,
+    ) -> String {
+        format!(
+            "{id} v{api_version} {session_id} {}",
+            locale.as_deref().unwrap_or("-")
+        )
+    }
+}
+
+async fn send(request: Request<Body>) -> (StatusCode, Value) {
+    let router = account_for_my_account_impls::rest_axum_router()
+        .with_state(Arc::new(RwLock::new(MyAccount)));
+    let response = router.oneshot(request).await.unwrap();
+    let status = response.status();
+    if !status.is_success() {
+        assert_eq!(
+            response.headers()["content-type"],
+            "application/problem+json"
+        );
+    }
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn headers_and_cookies_are_bound() {
+    let request = Request::get("/profiles/7")
+        .header("x-api-version", "2")
+        .header("cookie", "theme=dark; SID=abc123")
+        .header("locale", "fr")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = send(request).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "7 v2 abc123 fr");
+
+    // Optional headers may be left out.
+    let request = Request::get("/profiles/7")
+        .header("x-api-version", "2")
+        .header("cookie", "SID=abc123")
+        .body(Body::empty())
+        .unwrap();
+    let (_, body) = send(request).await;
+    assert_eq!(body, "7 v2 abc123 -");
+}
+
+#[tokio::test]
+async fn missing_or_malformed_values_are_problems() {
+    let cases = [
+        // Missing header.
+        Request::get("/profiles/7")
+            .header("cookie", "SID=abc123")
+            .body(Body::empty())
+            .unwrap(),
+        // Malformed header.
+        Request::get("/profiles/7")
+            .header("x-api-version", "two")
+            .header("cookie", "SID=abc123")
+            .body(Body::empty())
+            .unwrap(),
+        // Missing cookie.
+        Request::get("/profiles/7")
+            .header("x-api-version", "2")
+            .header("cookie", "theme=dark")
+            .body(Body::empty())
+            .unwrap(),
+    ];
+    for request in cases {
+        let (status, problem) = send(request).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{problem}");
+        assert_eq!(problem["status"], 400);
+        assert!(!problem["detail"].as_str().unwrap().is_empty());
+    }
+}
+
+#[tokio::test]
+async fn rest_client_sends_headers_and_cookies() {
+    let addr = ([127, 0, 0, 1], 39221).into();
+    tokio::spawn(account_for_my_account_impls::rest_axum(addr)(Arc::new(
+        RwLock::new(MyAccount),
+    )));
+    wait_for_server(addr).await;
+
+    let client = account_for_my_account_impls::AccountRestClient::new(format!("http://{}", addr));
+    let profile = client
+        .profile(7, 2, "abc123".to_string(), Some("fr".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(profile, "7 v2 abc123 fr");
+    let profile = client
+        .profile(7, 2, "abc123".to_string(), None)
+        .await
+        .unwrap();
+    assert_eq!(profile, "7 v2 abc123 -");
+}
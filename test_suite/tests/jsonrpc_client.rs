@@ -0,0 +1,97 @@
+//! The typed `{Trait}RpcClient`, decoding the original return types over HTTP and WebSocket.
+
+use std::sync::Arc;
+
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::ClientError;
+use jsonrpsee::http_client::HttpClientBuilder;
+use jsonrpsee::ws_client::WsClientBuilder;
+use multi_rpc::prelude::*;
+use multi_rpc_test_suite::wait_for_server;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::sync::RwLock;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Greeting {
+    pub text: String,
+    pub length: usize,
+}
+
+#[multi_rpc_trait(protocols(jsonrpsee))]
+#[allow(async_fn_in_trait)]
+pub trait Greeter {
+    async fn greet(&self, name: String) -> Greeting;
+    async fn find(&self, name: String) -> Option<String>;
+    async fn count(&self, name: String) -> Result<u64, RpcError>;
+    async fn reset(&self);
+}
+
+pub struct MyGreeter;
+
+#[multi_rpc_impl(protocols(jsonrpsee))]
+impl Greeter for MyGreeter {
+    async fn greet(&self, name: String) -> Greeting {
+        let text = format!("Hello, {}!", name);
+        Greeting {
+            length: text.len(),
+            text,
+        }
+    }
+
+    async fn find(&self, name: String) -> Option<String> {
+        (name == "Ada").then(|| "Lovelace".to_string())
+    }
+
+    async fn count(&self, name: String) -> Result<u64, RpcError> {
+        if name.is_empty() {
+            return Err(RpcError::InvalidArgument("name is empty".to_string()));
+        }
+        Ok(name.len() as u64)
+    }
+
+    async fn reset(&self) {}
+}
+
+// Calls every method through the typed client, checking the decoded values.
+async fn check_client(client: &(impl ClientT + Sync)) {
+    use greeter_protocols::GreeterRpcClient;
+
+    assert_eq!(
+        client.greet("Ada".to_string()).await.unwrap(),
+        Greeting {
+            text: "Hello, Ada!".to_string(),
+            length: 11,
+        }
+    );
+    assert_eq!(
+        client.find("Ada".to_string()).await.unwrap(),
+        Some("Lovelace".to_string())
+    );
+    assert_eq!(client.find("Bob".to_string()).await.unwrap(), None);
+    assert_eq!(client.count("Ada".to_string()).await.unwrap(), 3);
+    match client.count(String::new()).await {
+        Err(ClientError::Call(err)) => assert_eq!(err.code(), -32602),
+        other => panic!("expected an invalid argument error, got {:?}", other),
+    }
+    client.reset().await.unwrap();
+}
+
+#[tokio::test]
+async fn typed_clients_decode_return_types() {
+    let addr = ([127, 0, 0, 1], 39151).into();
+    let service = Arc::new(RwLock::new(MyGreeter));
+    tokio::spawn(greeter_for_my_greeter_impls::jsonrpsee(addr)(service));
+    wait_for_server(addr).await;
+
+    let http = HttpClientBuilder::default()
+        .build(format!("http://{}", addr))
+        .unwrap();
+    check_client(&http).await;
+
+    let ws = WsClientBuilder::default()
+        .build(format!("ws://{}", addr))
+        .await
+        .unwrap();
+    check_client(&ws).await;
+}
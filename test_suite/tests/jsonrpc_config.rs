@@ -0,0 +1,178 @@
+//! The settings of `JsonRpcConfig`, applied by `jsonrpsee_with_config`.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use jsonrpsee::ws_client::WsClientBuilder;
+use multi_rpc::config::JsonRpcTransports;
+use multi_rpc::prelude::*;
+use multi_rpc_test_suite::wait_for_server;
+use serde_json::json;
+use serde_json::Value;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+
+#[multi_rpc_trait(protocols(jsonrpsee))]
+#[allow(async_fn_in_trait)]
+pub trait Echo {
+    async fn echo(&self, text: String) -> String;
+}
+
+pub struct MyEcho;
+
+#[multi_rpc_impl(protocols(jsonrpsee))]
+impl Echo for MyEcho {
+    async fn echo(&self, text: String) -> String {
+        text
+    }
+}
+
+// Serves `MyEcho` on `port` with `config`.
+async fn serve(port: u16, config: JsonRpcConfig) -> (SocketAddr, JoinHandle<()>) {
+    let addr = ([127, 0, 0, 1], port).into();
+    let service = Arc::new(RwLock::new(MyEcho));
+    let task = tokio::spawn(echo_for_my_echo_impls::jsonrpsee_with_config(addr, config)(
+        service,
+    ));
+    wait_for_server(addr).await;
+    (addr, task)
+}
+
+// Posts `body` to `url`, returning the status and the body of the response.
+async fn post(url: String, body: String) -> (u16, String) {
+    let response = reqwest::Client::new()
+        .post(url)
+        .header("content-type", "application/json")
+        .body(body)
+        .send()
+        .await
+        .unwrap();
+    (response.status().as_u16(), response.text().await.unwrap())
+}
+
+fn echo_request(text: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": 1, "method": "echo", "params": [text] })
+}
+
+#[tokio::test]
+async fn only_the_configured_path_is_served() {
+    let (addr, _task) = serve(39171, JsonRpcConfig::new().path("/rpc")).await;
+
+    let (status, body) = post(format!("http://{addr}/rpc"), echo_request("hi").to_string()).await;
+    assert_eq!(status, 200);
+    assert_eq!(
+        serde_json::from_str::<Value>(&body).unwrap()["result"],
+        "hi"
+    );
+
+    let (status, _) = post(format!("http://{addr}/"), echo_request("hi").to_string()).await;
+    assert_eq!(status, 404);
+    let (status, _) = post(
+        format!("http://{addr}/rpc/x"),
+        echo_request("hi").to_string(),
+    )
+    .await;
+    assert_eq!(status, 404);
+}
+
+#[tokio::test]
+async fn batches_are_rejected_when_disabled() {
+    let (addr, _task) = serve(39172, JsonRpcConfig::new().disable_batch_requests()).await;
+
+    let batch = json!([echo_request("a"), echo_request("b")]);
+    let (_, body) = post(format!("http://{addr}"), batch.to_string()).await;
+    let response: Value = serde_json::from_str(&body).unwrap();
+    assert!(response["error"].is_object(), "{response}");
+    assert!(response.get("result").is_none());
+
+    // Single calls are still served.
+    let (status, _) = post(format!("http://{addr}"), echo_request("a").to_string()).await;
+    assert_eq!(status, 200);
+}
+
+#[tokio::test]
+async fn oversized_bodies_are_rejected() {
+    let (addr, _task) = serve(39173, JsonRpcConfig::new().max_request_body_size(256)).await;
+
+    let (status, body) = post(format!("http://{addr}"), echo_request("hi").to_string()).await;
+    assert_eq!(status, 200, "{body}");
+
+    let text = "x".repeat(1024);
+    let (status, body) = post(format!("http://{addr}"), echo_request(&text).to_string()).await;
+    assert_eq!(status, 413, "{body}");
+}
+
+#[tokio::test]
+async fn http_only_rejects_websockets() {
+    let (addr, _task) = serve(
+        39174,
+        JsonRpcConfig::new().transports(JsonRpcTransports::HttpOnly),
+    )
+    .await;
+
+    let (status, _) = post(format!("http://{addr}"), echo_request("hi").to_string()).await;
+    assert_eq!(status, 200);
+    assert!(WsClientBuilder::default()
+        .build(format!("ws://{addr}"))
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn ws_only_rejects_http() {
+    let (addr, _task) = serve(
+        39175,
+        JsonRpcConfig::new().transports(JsonRpcTransports::WsOnly),
+    )
+    .await;
+
+    let (status, _) = post(format!("http://{addr}"), echo_request("hi").to_string()).await;
+    assert_ne!(status, 200);
+    assert!(WsClientBuilder::default()
+        .build(format!("ws://{addr}"))
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn connections_beyond_the_maximum_are_rejected() {
+    let (addr, _task) = serve(39176, JsonRpcConfig::new().max_connections(1)).await;
+
+    let first = WsClientBuilder::default()
+        .build(format!("ws://{addr}"))
+        .await
+        .unwrap();
+    assert!(WsClientBuilder::default()
+        .build(format!("ws://{addr}"))
+        .await
+        .is_err());
+
+    // The connection is available again once the first client leaves.
+    drop(first);
+    for _ in 0..100 {
+        if WsClientBuilder::default()
+            .build(format!("ws://{addr}"))
+            .await
+            .is_ok()
+        {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("the connection of the dropped client was kept");
+}
+
+#[tokio::test]
+async fn aborting_the_server_closes_its_connections() {
+    let (addr, task) = serve(39177, JsonRpcConfig::new()).await;
+
+    let client = WsClientBuilder::default()
+        .build(format!("ws://{addr}"))
+        .await
+        .unwrap();
+    task.abort();
+    tokio::time::timeout(Duration::from_secs(5), client.on_disconnect())
+        .await
+        .expect("the connection outlived the server");
+}
@@ -0,0 +1,103 @@
+//! JSON-RPC method names, aliases and namespaces.
+
+use std::sync::Arc;
+
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::client::Error as ClientError;
+use jsonrpsee::core::client::SubscriptionClientT;
+use jsonrpsee::core::client::SubscriptionKind;
+use jsonrpsee::rpc_params;
+use jsonrpsee::ws_client::WsClient;
+use jsonrpsee::ws_client::WsClientBuilder;
+use multi_rpc::prelude::*;
+use multi_rpc_test_suite::wait_for_server;
+use tokio::sync::RwLock;
+
+#[multi_rpc_trait(protocols(jsonrpsee))]
+#[jsonrpc(namespace = "greeter")]
+#[allow(async_fn_in_trait)]
+pub trait Greeter {
+    #[jsonrpc(name = "getGreeting", aliases = ["greet"])]
+    async fn greet(&self, name: String) -> String;
+
+    #[jsonrpc(aliases = ["tick_stream"])]
+    async fn ticks(&self, count: u64) -> Streaming<u64>;
+}
+
+pub struct MyGreeter;
+
+#[multi_rpc_impl(protocols(jsonrpsee))]
+impl Greeter for MyGreeter {
+    async fn greet(&self, name: String) -> String {
+        format!("Hello, {}!", name)
+    }
+
+    async fn ticks(&self, count: u64) -> Streaming<u64> {
+        Streaming::new(futures::stream::iter(0..count))
+    }
+}
+
+// Serves `MyGreeter` on `port`, returning a client of it.
+async fn client(port: u16) -> WsClient {
+    let addr = ([127, 0, 0, 1], port).into();
+    let service = Arc::new(RwLock::new(MyGreeter));
+    tokio::spawn(greeter_for_my_greeter_impls::jsonrpsee(addr)(service));
+    wait_for_server(addr).await;
+    WsClientBuilder::default()
+        .build(format!("ws://{}", addr))
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn renamed_methods_are_namespaced() {
+    use greeter_protocols::GreeterRpcClient;
+
+    let client = client(39163).await;
+    let greeting: String = client
+        .request("greeter_getGreeting", rpc_params!["Ada"])
+        .await
+        .unwrap();
+    assert_eq!(greeting, "Hello, Ada!");
+
+    // Neither the Rust name nor the name outside the namespace is served.
+    for name in ["greeter_greet", "getGreeting"] {
+        match client.request::<String, _>(name, rpc_params!["Ada"]).await {
+            Err(ClientError::Call(err)) => assert_eq!(err.code(), -32601, "{name}"),
+            other => panic!("expected `{name}` to be unknown, got {:?}", other),
+        }
+    }
+
+    // The typed client uses the namespaced name.
+    assert_eq!(
+        client.greet("Ada".to_string()).await.unwrap(),
+        "Hello, Ada!"
+    );
+}
+
+#[tokio::test]
+async fn aliases_are_not_namespaced() {
+    let client = client(39164).await;
+    let greeting: String = client.request("greet", rpc_params!["Ada"]).await.unwrap();
+    assert_eq!(greeting, "Hello, Ada!");
+}
+
+#[tokio::test]
+async fn subscriptions_unsubscribe_within_the_namespace() {
+    let client = client(39165).await;
+    for (name, unsubscribe) in [
+        ("greeter_ticks", "greeter_unsubscribe_ticks"),
+        ("tick_stream", "unsubscribe_tick_stream"),
+    ] {
+        let mut subscription = client
+            .subscribe::<u64, _>(name, rpc_params![u64::MAX], unsubscribe)
+            .await
+            .unwrap();
+        assert_eq!(subscription.next().await.unwrap().unwrap(), 0);
+        let SubscriptionKind::Subscription(id) = subscription.kind() else {
+            panic!("`{name}` isn't a subscription");
+        };
+        let unsubscribed: bool = client.request(unsubscribe, rpc_params![id]).await.unwrap();
+        assert!(unsubscribed, "{unsubscribe}");
+    }
+}
@@ -0,0 +1,101 @@
+//! JSON-RPC params by position and by name, and the `client_params` of the typed client.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use axum::routing::post;
+use axum::Json;
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::params::ArrayParams;
+use jsonrpsee::core::params::ObjectParams;
+use jsonrpsee::http_client::HttpClientBuilder;
+use multi_rpc::prelude::*;
+use multi_rpc_test_suite::wait_for_server;
+use serde_json::json;
+use serde_json::Value;
+use tokio::sync::RwLock;
+
+#[multi_rpc_trait(protocols(jsonrpsee))]
+#[jsonrpc(client_params = named)]
+#[allow(async_fn_in_trait)]
+pub trait Settings {
+    async fn update_settings(&self, user_id: u64, brightness: u32, theme: String) -> String;
+}
+
+pub struct MySettings;
+
+#[multi_rpc_impl(protocols(jsonrpsee))]
+impl Settings for MySettings {
+    async fn update_settings(&self, user_id: u64, brightness: u32, theme: String) -> String {
+        format!("{user_id}: {brightness} {theme}")
+    }
+}
+
+#[tokio::test]
+async fn server_accepts_params_by_position_and_by_name() {
+    use settings_protocols::SettingsRpcClient;
+
+    let addr = ([127, 0, 0, 1], 39161).into();
+    let service = Arc::new(RwLock::new(MySettings));
+    tokio::spawn(settings_for_my_settings_impls::jsonrpsee(addr)(service));
+    wait_for_server(addr).await;
+    let client = HttpClientBuilder::default()
+        .build(format!("http://{}", addr))
+        .unwrap();
+
+    let mut positional = ArrayParams::new();
+    positional.insert(101).unwrap();
+    positional.insert(85).unwrap();
+    positional.insert("dark").unwrap();
+    let updated: String = client.request("update_settings", positional).await.unwrap();
+    assert_eq!(updated, "101: 85 dark");
+
+    let mut named = ObjectParams::new();
+    named.insert("user_id", 101).unwrap();
+    named.insert("brightness", 85).unwrap();
+    named.insert("theme", "dark").unwrap();
+    let updated: String = client.request("update_settings", named).await.unwrap();
+    assert_eq!(updated, "101: 85 dark");
+
+    // The typed client sends named params to the real server.
+    let updated = client
+        .update_settings(7, 10, "light".to_string())
+        .await
+        .unwrap();
+    assert_eq!(updated, "7: 10 light");
+}
+
+#[tokio::test]
+async fn named_client_sends_an_object() {
+    use settings_protocols::SettingsRpcClient;
+
+    // Records the params of the requests, answering them with a fixed result.
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let spy = axum::Router::new().route(
+        "/",
+        post({
+            let received = received.clone();
+            move |Json(request): Json<Value>| async move {
+                received.lock().unwrap().push(request["params"].clone());
+                Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": "ok" }))
+            }
+        }),
+    );
+    let addr: SocketAddr = ([127, 0, 0, 1], 39162).into();
+    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    tokio::spawn(async move { axum::serve(listener, spy).await });
+
+    let client = HttpClientBuilder::default()
+        .build(format!("http://{}", addr))
+        .unwrap();
+    let result = client
+        .update_settings(101, 85, "dark".to_string())
+        .await
+        .unwrap();
+    assert_eq!(result, "ok");
+    assert_eq!(
+        *received.lock().unwrap(),
+        [json!({ "user_id": 101, "brightness": 85, "theme": "dark" })]
+    );
+}
@@ -0,0 +1,107 @@
+//! The OpenAPI document of the REST endpoints.
+
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::http::Request;
+use axum::http::StatusCode;
+use multi_rpc::prelude::*;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use tokio::sync::RwLock;
+use tower::ServiceExt;
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct Item {
+    pub name: String,
+}
+
+// Doesn't implement `JsonSchema`.
+#[derive(Serialize, Deserialize)]
+pub struct Opaque {
+    pub name: String,
+}
+
+#[multi_rpc_trait(protocols(rest_axum))]
+#[allow(async_fn_in_trait)]
+pub trait Store {
+    async fn item(&self, id: u32) -> Item;
+    async fn opaque(&self, id: u32) -> Opaque;
+    async fn create(&self, name: String, note: Option<String>) -> Item;
+    async fn touch(&self, id: u32, note: Option<String>) -> Item;
+}
+
+pub struct MyStore;
+
+#[multi_rpc_impl(protocols(rest_axum))]
+impl Store for MyStore {
+    #[rest(method = GET, path = "/items/{id}")]
+    async fn item(&self, id: u32) -> Item {
+        Item {
+            name: id.to_string(),
+        }
+    }
+
+    #[rest(method = GET, path = "/opaque/{id}")]
+    async fn opaque(&self, id: u32) -> Opaque {
+        Opaque {
+            name: id.to_string(),
+        }
+    }
+
+    #[rest(method = POST, path = "/items", body(name, note))]
+    async fn create(&self, name: String, note: Option<String>) -> Item {
+        let _ = note;
+        Item { name }
+    }
+
+    // Every body field is optional, so the body may be left out.
+    #[rest(method = POST, path = "/items/{id}/touch", body(note))]
+    async fn touch(&self, id: u32, note: Option<String>) -> Item {
+        Item {
+            name: format!("{id} {}", note.as_deref().unwrap_or("-")),
+        }
+    }
+}
+
+fn response_schema(document: &serde_json::Value, path: &str) -> serde_json::Value {
+    document["paths"][path]["get"]["responses"]["200"]["content"]["application/json"]["schema"]
+        .clone()
+}
+
+#[test]
+fn types_without_json_schema_are_described_by_the_empty_schema() {
+    let document = store_for_my_store_impls::openapi();
+
+    assert_eq!(
+        response_schema(&document, "/items/{id}"),
+        json!({ "$ref": "#/components/schemas/Item" })
+    );
+    assert_eq!(response_schema(&document, "/opaque/{id}"), json!({}));
+    assert!(document["components"]["schemas"]["Opaque"].is_null());
+}
+
+#[tokio::test]
+async fn request_bodies_are_required_unless_every_field_is_optional() {
+    let document = store_for_my_store_impls::openapi();
+    let request_body = |path: &str| document["paths"][path]["post"]["requestBody"].clone();
+    assert_eq!(request_body("/items")["required"], true);
+    assert_eq!(
+        request_body("/items")["content"]["application/json"]["schema"]["required"],
+        json!(["name"])
+    );
+    assert_eq!(request_body("/items/{id}/touch")["required"], false);
+
+    // As described, the server accepts the request without a body.
+    let router =
+        store_for_my_store_impls::rest_axum_router().with_state(Arc::new(RwLock::new(MyStore)));
+    let request = Request::post("/items/7/touch").body(Body::empty()).unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, r#"{"name":"7 -"}"#);
+}
@@ -0,0 +1,98 @@
+//! The OpenRPC document of the JSON-RPC methods.
+
+use multi_rpc::prelude::*;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct Item {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, RpcError, thiserror::Error)]
+pub enum StoreError {
+    #[error("missing")]
+    #[rpc(status = 404, code = -32004)]
+    Missing,
+    #[error("gone")]
+    #[rpc(status = 410, code = -32004)]
+    Gone,
+    #[error("broken")]
+    Broken,
+}
+
+// Doesn't implement `JsonSchema`.
+#[derive(Serialize, Deserialize)]
+pub struct Opaque {
+    pub name: String,
+}
+
+#[multi_rpc_trait(protocols(jsonrpsee))]
+#[allow(async_fn_in_trait)]
+pub trait Store {
+    async fn item(&self, id: u32) -> Item;
+    async fn opaque(&self, opaque: Opaque) -> Opaque;
+    async fn lookup(&self, id: u32) -> Result<Item, RpcError>;
+    async fn remove(&self, id: u32) -> Result<(), StoreError>;
+    async fn rename(&self, name: String) -> Result<(), String>;
+}
+
+fn method<'a>(document: &'a serde_json::Value, name: &str) -> &'a serde_json::Value {
+    document["methods"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|method| method["name"] == name)
+        .unwrap()
+}
+
+#[test]
+fn types_without_json_schema_are_described_by_the_empty_schema() {
+    let document = store_protocols::openrpc();
+
+    let item = method(&document, "item");
+    assert_eq!(
+        item["result"]["schema"],
+        json!({ "$ref": "#/components/schemas/Item" })
+    );
+
+    let opaque = method(&document, "opaque");
+    assert_eq!(opaque["params"][0]["schema"], json!({}));
+    assert_eq!(opaque["result"]["schema"], json!({}));
+    assert!(document["components"]["schemas"]["Opaque"].is_null());
+}
+
+#[test]
+fn errors_are_the_codes_of_the_error_type() {
+    let document = store_protocols::openrpc();
+
+    assert!(method(&document, "item")["errors"].is_null());
+
+    let codes: Vec<_> = method(&document, "lookup")["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|error| error["code"].as_i64().unwrap())
+        .collect();
+    assert_eq!(
+        codes,
+        [-32603, -32004, -32602, -32001, -32003, -32009, -32053, -32054, -32029]
+    );
+
+    // Codes shared by several variants are listed once.
+    assert_eq!(
+        method(&document, "remove")["errors"],
+        json!([
+            { "code": -32004, "message": "Missing" },
+            { "code": -32603, "message": "Broken" },
+        ])
+    );
+
+    // Other errors are always reported as internal errors.
+    assert_eq!(
+        method(&document, "rename")["errors"],
+        json!([{ "code": -32603, "message": "Internal error" }])
+    );
+}
@@ -0,0 +1,126 @@
+//! `application/problem+json` responses for method errors and rejected input.
+
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::http::Request;
+use axum::http::StatusCode;
+use multi_rpc::prelude::*;
+use serde_json::Value;
+use tokio::sync::RwLock;
+use tower::ServiceExt;
+
+#[multi_rpc_trait(protocols(rest_axum))]
+#[allow(async_fn_in_trait)]
+pub trait Store {
+    async fn item(&self, id: u32) -> Result<String, RpcError>;
+    async fn search(&self, limit: u32) -> Vec<u32>;
+    async fn create(&self, name: String, price: u32) -> String;
+    async fn login(&self, username: String, password: String) -> bool;
+}
+
+pub struct MyStore;
+
+#[multi_rpc_impl(protocols(rest_axum))]
+impl Store for MyStore {
+    #[rest(method = GET, path = "/items/{id}")]
+    async fn item(&self, id: u32) -> Result<String, RpcError> {
+        match id {
+            1 => Ok("book".to_string()),
+            2 => Err(RpcError::PermissionDenied("item 2 is private".to_string())),
+            _ => Err(RpcError::NotFound(format!("item {id} not found"))),
+        }
+    }
+
+    #[rest(method = GET, path = "/search", query(limit))]
+    async fn search(&self, limit: u32) -> Vec<u32> {
+        (0..limit).collect()
+    }
+
+    #[rest(method = POST, path = "/items", body(name, price))]
+    async fn create(&self, name: String, price: u32) -> String {
+        format!("{name}: {price}")
+    }
+
+    #[rest(method = POST, path = "/login", form(username, password))]
+    async fn login(&self, username: String, password: String) -> bool {
+        username == password
+    }
+}
+
+// Sends `request`, checking that the response is a problem with the standard members, and
+// returns its status and problem.
+async fn send(request: Request<Body>) -> (StatusCode, Value) {
+    let router =
+        store_for_my_store_impls::rest_axum_router().with_state(Arc::new(RwLock::new(MyStore)));
+    let response = router.oneshot(request).await.unwrap();
+    let status = response.status();
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/problem+json",
+        "{status}"
+    );
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let problem: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(problem["type"], "about:blank", "{problem}");
+    assert_eq!(problem["title"], status.canonical_reason().unwrap());
+    assert_eq!(problem["status"], status.as_u16());
+    assert!(!problem["detail"].as_str().unwrap().is_empty(), "{problem}");
+    (status, problem)
+}
+
+fn get(uri: &str) -> Request<Body> {
+    Request::get(uri).body(Body::empty()).unwrap()
+}
+
+fn post(uri: &str, content_type: &str, body: &'static str) -> Request<Body> {
+    Request::post(uri)
+        .header("content-type", content_type)
+        .body(Body::from(body))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn method_errors_are_problems() {
+    // The detail is the `Display` of the error.
+    let (status, problem) = send(get("/items/7")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(problem["detail"], "Not found: item 7 not found");
+
+    let (status, problem) = send(get("/items/2")).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(problem["detail"], "Permission denied: item 2 is private");
+}
+
+#[tokio::test]
+async fn rejected_paths_are_problems() {
+    let (status, _) = send(get("/items/abc")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn rejected_queries_are_problems() {
+    let (status, _) = send(get("/search?limit=many")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(get("/search")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn rejected_json_bodies_are_problems() {
+    let (status, _) = send(post("/items", "application/json", "{")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(post("/items", "application/json", r#"{"name":"book"}"#)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let (status, _) = send(post("/items", "text/plain", r#"{"name":"book","price":3}"#)).await;
+    assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+#[tokio::test]
+async fn rejected_forms_are_problems() {
+    let form = "application/x-www-form-urlencoded";
+    let (status, _) = send(post("/login", form, "username=ada")).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
@@ -0,0 +1,120 @@
+//! `produces = ...` raw and JSON bodies, and `IntoRestResponse` return values.
+
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::http::header;
+use axum::http::Request;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use multi_rpc::prelude::*;
+use multi_rpc_test_suite::wait_for_server;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::sync::RwLock;
+use tower::ServiceExt;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    pub id: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Csv(String);
+
+impl IntoRestResponse for Csv {
+    fn into_rest_response(self, status: StatusCode) -> axum::response::Response {
+        (status, [(header::CONTENT_TYPE, "text/csv")], self.0).into_response()
+    }
+}
+
+#[multi_rpc_trait(protocols(rest_axum))]
+#[allow(async_fn_in_trait)]
+pub trait Exports {
+    async fn metrics(&self) -> String;
+    async fn download(&self) -> Vec<u8>;
+    async fn report(&self) -> Report;
+    async fn csv(&self) -> Csv;
+}
+
+pub struct MyExports;
+
+#[multi_rpc_impl(protocols(rest_axum))]
+impl Exports for MyExports {
+    #[rest(method = GET, path = "/metrics", produces = "text/plain")]
+    async fn metrics(&self) -> String {
+        "requests 7\n".to_string()
+    }
+
+    #[rest(method = GET, path = "/download", produces = "application/octet-stream")]
+    async fn download(&self) -> Vec<u8> {
+        vec![0, 159, 255]
+    }
+
+    #[rest(method = GET, path = "/report", produces = "application/vnd.api+json")]
+    async fn report(&self) -> Report {
+        Report { id: 7 }
+    }
+
+    #[rest(method = GET, path = "/export.csv")]
+    async fn csv(&self) -> Csv {
+        Csv("id\n7\n".to_string())
+    }
+}
+
+fn service() -> Arc<RwLock<MyExports>> {
+    Arc::new(RwLock::new(MyExports))
+}
+
+// The `Content-Type` and body of the response to `GET uri`.
+async fn get(uri: &str) -> (String, Vec<u8>) {
+    let router = exports_for_my_exports_impls::rest_axum_router().with_state(service());
+    let request = Request::get(uri).body(Body::empty()).unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let content_type = response.headers()[header::CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (content_type, body.to_vec())
+}
+
+#[tokio::test]
+async fn raw_bodies_are_sent_as_is() {
+    let (content_type, body) = get("/metrics").await;
+    assert_eq!(content_type, "text/plain");
+    assert_eq!(body, b"requests 7\n");
+
+    let (content_type, body) = get("/download").await;
+    assert_eq!(content_type, "application/octet-stream");
+    assert_eq!(body, [0, 159, 255]);
+}
+
+#[tokio::test]
+async fn json_media_types_send_json() {
+    let (content_type, body) = get("/report").await;
+    assert_eq!(content_type, "application/vnd.api+json");
+    assert_eq!(body, br#"{"id":7}"#);
+}
+
+#[tokio::test]
+async fn into_rest_response_values_build_the_response() {
+    let (content_type, body) = get("/export.csv").await;
+    assert_eq!(content_type, "text/csv");
+    assert_eq!(body, b"id\n7\n");
+}
+
+#[tokio::test]
+async fn rest_client_decodes_raw_and_json_bodies() {
+    let addr = ([127, 0, 0, 1], 39241).into();
+    tokio::spawn(exports_for_my_exports_impls::rest_axum(addr)(service()));
+    wait_for_server(addr).await;
+
+    let client = exports_for_my_exports_impls::ExportsRestClient::new(format!("http://{}", addr));
+    assert_eq!(client.metrics().await.unwrap(), "requests 7\n");
+    assert_eq!(client.download().await.unwrap(), [0, 159, 255]);
+    assert_eq!(client.report().await.unwrap(), Report { id: 7 });
+}
@@ -0,0 +1,147 @@
+//! The generated `{Trait}Remote` clients.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use jsonrpsee::http_client::HttpClient;
+use multi_rpc::prelude::*;
+use multi_rpc_test_suite::wait_for_server;
+use tokio::sync::RwLock;
+
+#[multi_rpc_trait(protocols(jsonrpsee), remote)]
+#[jsonrpc(namespace = "greeter")]
+#[allow(async_fn_in_trait)]
+pub trait Greeter {
+    #[jsonrpc(name = "getGreeting")]
+    async fn greet(&self, name: String) -> Result<String, RpcError>;
+}
+
+pub struct MyGreeter;
+
+// Served over every protocol.
+#[multi_rpc_trait(remote)]
+#[allow(async_fn_in_trait)]
+pub trait Directory {
+    async fn find(&self, name: String) -> Result<String, RpcError>;
+    async fn greet(&self, name: String) -> Result<String, RpcError>;
+    async fn ping(&self) -> Result<(), RpcError>;
+}
+
+pub struct MyDirectory;
+
+#[multi_rpc_impl(protocols(jsonrpsee))]
+impl Greeter for MyGreeter {
+    async fn greet(&self, name: String) -> Result<String, RpcError> {
+        Ok(format!("Hello, {}!", name))
+    }
+}
+
+#[multi_rpc_impl]
+impl Directory for MyDirectory {
+    #[rest(method = GET, path = "/find/{name}")]
+    async fn find(&self, name: String) -> Result<String, RpcError> {
+        match name.as_str() {
+            "Ada" => Ok("Lovelace".to_string()),
+            _ => Err(RpcError::NotFound(name)),
+        }
+    }
+
+    #[rest(method = GET, path = "/greet/{name}")]
+    async fn greet(&self, name: String) -> Result<String, RpcError> {
+        Ok(format!("Hello, {}!", name))
+    }
+
+    #[rest(method = POST, path = "/ping")]
+    async fn ping(&self) -> Result<(), RpcError> {
+        Ok(())
+    }
+}
+
+fn directory() -> Arc<RwLock<MyDirectory>> {
+    Arc::new(RwLock::new(MyDirectory))
+}
+
+// Calls every method of `directory`, checking that errors keep their kind.
+async fn check_directory(directory: directory_protocols::DirectoryRemote) {
+    assert_eq!(directory.find("Ada".to_string()).await.unwrap(), "Lovelace");
+    match directory.find("Bob".to_string()).await {
+        Err(RpcError::NotFound(message)) => assert!(message.contains("Bob"), "{message}"),
+        other => panic!("expected `NotFound`, got {:?}", other),
+    }
+    assert_eq!(
+        directory.greet("Ada".to_string()).await.unwrap(),
+        "Hello, Ada!"
+    );
+    directory.ping().await.unwrap();
+}
+
+#[tokio::test]
+async fn tarpc_remote_keeps_error_kinds() {
+    let addr = ([127, 0, 0, 1], 39112).into();
+    tokio::spawn(directory_for_my_directory_impls::tarpc_tcp(addr)(
+        directory(),
+    ));
+    wait_for_server(addr).await;
+
+    let client = directory_protocols::connect_tarpc(addr, multi_rpc::codec::TarpcCodec::Json)
+        .await
+        .unwrap();
+    check_directory(directory_protocols::DirectoryRemote::new(client)).await;
+}
+
+#[tokio::test]
+async fn rest_remote_keeps_error_kinds() {
+    let addr = ([127, 0, 0, 1], 39113).into();
+    tokio::spawn(directory_for_my_directory_impls::rest_axum(addr)(
+        directory(),
+    ));
+    wait_for_server(addr).await;
+
+    let client =
+        directory_for_my_directory_impls::DirectoryRestClient::new(format!("http://{}", addr));
+    check_directory(directory_protocols::DirectoryRemote::new(client)).await;
+}
+
+#[tokio::test]
+async fn jsonrpc_remote_keeps_error_kinds() {
+    let addr = ([127, 0, 0, 1], 39114).into();
+    tokio::spawn(directory_for_my_directory_impls::jsonrpsee(addr)(
+        directory(),
+    ));
+    wait_for_server(addr).await;
+
+    let client = HttpClient::builder()
+        .build(format!("http://{}", addr))
+        .unwrap();
+    check_directory(directory_protocols::DirectoryRemote::jsonrpc(client)).await;
+}
+
+#[tokio::test]
+async fn failed_calls_return_the_error() {
+    // Nothing listens on this port.
+    let addr: SocketAddr = ([127, 0, 0, 1], 39115).into();
+    let client =
+        directory_for_my_directory_impls::DirectoryRestClient::new(format!("http://{}", addr));
+    let directory = directory_protocols::DirectoryRemote::new(client);
+    match directory.greet("Ada".to_string()).await {
+        Err(RpcError::Unavailable(_)) => {}
+        other => panic!("expected `Unavailable`, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn jsonrpc_remote_calls_renamed_methods() {
+    let addr = ([127, 0, 0, 1], 39111).into();
+    let service = Arc::new(RwLock::new(MyGreeter));
+    tokio::spawn(greeter_for_my_greeter_impls::jsonrpsee(addr)(service));
+    wait_for_server(addr).await;
+
+    let client = HttpClient::builder()
+        .build(format!("http://{}", addr))
+        .unwrap();
+    let greeter = greeter_protocols::GreeterRemote::jsonrpc(client);
+    assert_eq!(
+        greeter.greet("Ada".to_string()).await.unwrap(),
+        "Hello, Ada!"
+    );
+}
@@ -0,0 +1,220 @@
+//! Methods taking a `&RequestContext`, over every protocol.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use axum::body::Body;
+use axum::extract::ConnectInfo;
+use axum::http::Request;
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::http_client::HeaderMap;
+use jsonrpsee::http_client::HeaderValue;
+use jsonrpsee::http_client::HttpClientBuilder;
+use jsonrpsee::rpc_params;
+use multi_rpc::client::rest::RestClient;
+use multi_rpc::context::Identity;
+use multi_rpc::prelude::*;
+use multi_rpc_test_suite::wait_for_server;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::sync::RwLock;
+use tower::ServiceExt;
+
+// What a method saw of its request.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Seen {
+    pub greeting: String,
+    pub protocol: String,
+    pub peer_ip: Option<String>,
+    // Whole seconds left until the deadline.
+    pub deadline_secs: Option<u64>,
+    pub trace_id: Option<String>,
+    pub header: Option<String>,
+    pub identity: Option<String>,
+}
+
+#[multi_rpc_trait]
+#[allow(async_fn_in_trait)]
+pub trait Inspector {
+    async fn inspect(&self, ctx: &RequestContext, greeting: String) -> Seen;
+}
+
+pub struct MyInspector;
+
+#[multi_rpc_impl]
+impl Inspector for MyInspector {
+    #[rest(method = GET, path = "/inspect", query(greeting))]
+    async fn inspect(&self, ctx: &RequestContext, greeting: String) -> Seen {
+        Seen {
+            greeting,
+            protocol: ctx.protocol().to_string(),
+            peer_ip: ctx.peer_addr().map(|addr| addr.ip().to_string()),
+            deadline_secs: ctx
+                .deadline()
+                .map(|deadline| deadline.saturating_duration_since(Instant::now()).as_secs()),
+            trace_id: ctx.trace_id().map(str::to_string),
+            header: ctx
+                .headers()
+                .get("x-test")
+                .map(|value| value.to_str().unwrap().to_string()),
+            identity: ctx.identity().map(|Identity(name)| name.clone()),
+        }
+    }
+}
+
+fn service() -> Arc<RwLock<MyInspector>> {
+    Arc::new(RwLock::new(MyInspector))
+}
+
+const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+#[tokio::test]
+async fn tarpc_passes_the_deadline_and_trace_id() {
+    let addr = ([127, 0, 0, 1], 39191).into();
+    tokio::spawn(inspector_for_my_inspector_impls::tarpc_tcp(addr)(service()));
+    wait_for_server(addr).await;
+
+    let client = inspector_protocols::connect_tarpc(addr, TarpcCodec::Json)
+        .await
+        .unwrap();
+    let mut ctx = tarpc::context::current();
+    ctx.deadline = Instant::now() + Duration::from_secs(60);
+    ctx.trace_context.trace_id = tarpc::trace::TraceId::from(0x1234);
+    // The client takes tarpc's context, but not the `RequestContext`.
+    let seen = client.inspect(ctx, "hi".to_string()).await.unwrap();
+
+    assert_eq!(seen.greeting, "hi");
+    assert_eq!(seen.protocol, "tarpc");
+    assert_eq!(seen.peer_ip.as_deref(), Some("127.0.0.1"));
+    let deadline_secs = seen.deadline_secs.unwrap();
+    assert!((50..=60).contains(&deadline_secs), "{deadline_secs}");
+    assert_eq!(
+        seen.trace_id,
+        Some(tarpc::trace::TraceId::from(0x1234).to_string())
+    );
+    assert_eq!(seen.header, None);
+    assert_eq!(seen.identity, None);
+}
+
+#[tokio::test]
+async fn rest_passes_the_peer_address_and_headers() {
+    let addr = ([127, 0, 0, 1], 39192).into();
+    tokio::spawn(inspector_for_my_inspector_impls::rest_axum(addr)(service()));
+    wait_for_server(addr).await;
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-test", HeaderValue::from_static("rest"));
+    headers.insert("traceparent", HeaderValue::from_static(TRACEPARENT));
+    let http = reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap();
+    // The generated client only takes the wire arguments.
+    let client = inspector_for_my_inspector_impls::InspectorRestClient::from_client(
+        RestClient::with_client(http, format!("http://{}", addr)),
+    );
+    let seen = client.inspect("hi".to_string()).await.unwrap();
+
+    assert_eq!(
+        seen,
+        Seen {
+            greeting: "hi".to_string(),
+            protocol: "rest_axum".to_string(),
+            peer_ip: Some("127.0.0.1".to_string()),
+            deadline_secs: None,
+            trace_id: Some("4bf92f3577b34da6a3ce929d0e0e4736".to_string()),
+            header: Some("rest".to_string()),
+            identity: None,
+        }
+    );
+}
+
+#[tokio::test]
+async fn rest_passes_the_identity_of_the_request_extensions() {
+    let router = inspector_for_my_inspector_impls::rest_axum_router().with_state(service());
+
+    let peer: SocketAddr = ([10, 0, 0, 1], 4000).into();
+    let mut request = Request::get("/inspect?greeting=hi")
+        .header("x-request-id", "req-1")
+        .body(Body::empty())
+        .unwrap();
+    request.extensions_mut().insert(ConnectInfo(peer));
+    request.extensions_mut().insert(Identity("ada".to_string()));
+    let response = router.oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let seen: Seen = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(seen.peer_ip.as_deref(), Some("10.0.0.1"));
+    assert_eq!(seen.trace_id.as_deref(), Some("req-1"));
+    assert_eq!(seen.identity.as_deref(), Some("ada"));
+}
+
+#[tokio::test]
+async fn jsonrpc_passes_headers_and_authenticates() {
+    use inspector_protocols::InspectorRpcClient;
+
+    let addr = ([127, 0, 0, 1], 39193).into();
+    let config = JsonRpcConfig::new().authenticate(|ctx| {
+        let token = ctx.headers().get("authorization")?.to_str().ok()?;
+        token
+            .strip_prefix("Bearer ")
+            .map(|name| Identity(name.to_string()))
+    });
+    tokio::spawn(inspector_for_my_inspector_impls::jsonrpsee_with_config(
+        addr, config,
+    )(service()));
+    wait_for_server(addr).await;
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-test", HeaderValue::from_static("jsonrpc"));
+    headers.insert("traceparent", HeaderValue::from_static(TRACEPARENT));
+    headers.insert("authorization", HeaderValue::from_static("Bearer ada"));
+    let client = HttpClientBuilder::default()
+        .set_headers(headers)
+        .build(format!("http://{}", addr))
+        .unwrap();
+    // The generated client only takes the wire arguments.
+    let seen = client.inspect("hi".to_string()).await.unwrap();
+
+    assert_eq!(
+        seen,
+        Seen {
+            greeting: "hi".to_string(),
+            protocol: "jsonrpsee".to_string(),
+            peer_ip: Some("127.0.0.1".to_string()),
+            deadline_secs: None,
+            trace_id: Some("4bf92f3577b34da6a3ce929d0e0e4736".to_string()),
+            header: Some("jsonrpc".to_string()),
+            identity: Some("ada".to_string()),
+        }
+    );
+
+    // Without credentials, the call is served without an identity.
+    let anonymous = HttpClientBuilder::default()
+        .build(format!("http://{}", addr))
+        .unwrap();
+    let seen = anonymous.inspect("hi".to_string()).await.unwrap();
+    assert_eq!(seen.identity, None);
+}
+
+#[tokio::test]
+async fn jsonrpc_params_exclude_the_context() {
+    let addr = ([127, 0, 0, 1], 39194).into();
+    tokio::spawn(inspector_for_my_inspector_impls::jsonrpsee(addr)(service()));
+    wait_for_server(addr).await;
+
+    let client = HttpClientBuilder::default()
+        .build(format!("http://{}", addr))
+        .unwrap();
+    let seen: Seen = client.request("inspect", rpc_params!["hi"]).await.unwrap();
+    assert_eq!(seen.greeting, "hi");
+
+    let document = inspector_protocols::openrpc();
+    let params = &document["methods"][0]["params"];
+    assert_eq!(params.as_array().unwrap().len(), 1);
+    assert_eq!(params[0]["name"], "greeting");
+}
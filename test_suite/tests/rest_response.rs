@@ -0,0 +1,181 @@
+//! The status and headers of REST responses, and the plain values sent over tarpc and JSON-RPC.
+
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::http::Request;
+use axum::http::StatusCode;
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::http_client::HttpClientBuilder;
+use jsonrpsee::rpc_params;
+use multi_rpc::prelude::*;
+use multi_rpc_test_suite::wait_for_server;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::sync::RwLock;
+use tower::ServiceExt;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct User {
+    pub id: u32,
+    pub name: String,
+}
+
+#[multi_rpc_trait]
+#[allow(async_fn_in_trait)]
+pub trait Users {
+    async fn create(&self, name: String) -> RestResponse<User>;
+    async fn delete(&self, id: u32);
+    async fn archive(&self, id: u32) -> Result<RestResponse<Option<User>>, RpcError>;
+    async fn clear_note(&self, id: u32) -> String;
+    async fn clear_avatar(&self, id: u32) -> RestResponse<Vec<u8>>;
+}
+
+pub struct MyUsers;
+
+#[multi_rpc_impl]
+impl Users for MyUsers {
+    #[rest(method = POST, path = "/users", body(name))]
+    async fn create(&self, name: String) -> RestResponse<User> {
+        RestResponse::created("/users/7", User { id: 7, name }).with_cache_control("no-store")
+    }
+
+    #[rest(method = DELETE, path = "/users/{id}", status = 204)]
+    async fn delete(&self, id: u32) {
+        let _ = id;
+    }
+
+    // Answers `204 No Content` at runtime.
+    #[rest(method = POST, path = "/users/{id}/archive")]
+    async fn archive(&self, id: u32) -> Result<RestResponse<Option<User>>, RpcError> {
+        let _ = id;
+        Ok(RestResponse::new(None).with_status(StatusCode::NO_CONTENT))
+    }
+
+    // Raw bodies are left out as well.
+    #[rest(
+        method = DELETE,
+        path = "/users/{id}/note",
+        produces = "text/plain",
+        status = 204
+    )]
+    async fn clear_note(&self, id: u32) -> String {
+        format!("note of {id} cleared")
+    }
+
+    #[rest(
+        method = DELETE,
+        path = "/users/{id}/avatar",
+        produces = "application/octet-stream"
+    )]
+    async fn clear_avatar(&self, id: u32) -> RestResponse<Vec<u8>> {
+        let _ = id;
+        RestResponse::new(vec![1, 2, 3]).with_status(StatusCode::NO_CONTENT)
+    }
+}
+
+fn service() -> Arc<RwLock<MyUsers>> {
+    Arc::new(RwLock::new(MyUsers))
+}
+
+#[tokio::test]
+async fn rest_sets_the_status_and_headers() {
+    let router = users_for_my_users_impls::rest_axum_router().with_state(service());
+
+    let request = Request::post("/users")
+        .header("content-type", "application/json")
+        .body(Body::from(r#"{"name":"Ada"}"#))
+        .unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(response.headers()["location"], "/users/7");
+    assert_eq!(response.headers()["cache-control"], "no-store");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, r#"{"id":7,"name":"Ada"}"#);
+
+    for request in [
+        Request::delete("/users/7").body(Body::empty()).unwrap(),
+        Request::post("/users/7/archive")
+            .body(Body::empty())
+            .unwrap(),
+        Request::delete("/users/7/note")
+            .body(Body::empty())
+            .unwrap(),
+        Request::delete("/users/7/avatar")
+            .body(Body::empty())
+            .unwrap(),
+    ] {
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(response.headers().get("content-type").is_none());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(body.is_empty());
+    }
+}
+
+#[tokio::test]
+async fn rest_client_accepts_no_content() {
+    let addr = ([127, 0, 0, 1], 39211).into();
+    tokio::spawn(users_for_my_users_impls::rest_axum(addr)(service()));
+    wait_for_server(addr).await;
+
+    let client = users_for_my_users_impls::UsersRestClient::new(format!("http://{}", addr));
+    let created = client.create("Ada".to_string()).await.unwrap();
+    assert_eq!(
+        created.into_inner(),
+        User {
+            id: 7,
+            name: "Ada".to_string()
+        }
+    );
+    client.delete(7).await.unwrap();
+    assert_eq!(client.archive(7).await.unwrap().into_inner(), None);
+    assert_eq!(client.clear_note(7).await.unwrap(), "");
+    assert!(client
+        .clear_avatar(7)
+        .await
+        .unwrap()
+        .into_inner()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn tarpc_returns_the_value() {
+    let addr = ([127, 0, 0, 1], 39212).into();
+    tokio::spawn(users_for_my_users_impls::tarpc_tcp(addr)(service()));
+    wait_for_server(addr).await;
+
+    let client = users_protocols::connect_tarpc(addr, TarpcCodec::Json)
+        .await
+        .unwrap();
+    let created = client
+        .create(tarpc::context::current(), "Ada".to_string())
+        .await
+        .unwrap();
+    // Only the value is sent, without the status or headers.
+    assert_eq!(created.status(), None);
+    assert!(created.headers().is_empty());
+    assert_eq!(created.value().id, 7);
+    client.delete(tarpc::context::current(), 7).await.unwrap();
+}
+
+#[tokio::test]
+async fn jsonrpc_returns_the_value() {
+    let addr = ([127, 0, 0, 1], 39213).into();
+    tokio::spawn(users_for_my_users_impls::jsonrpsee(addr)(service()));
+    wait_for_server(addr).await;
+
+    let client = HttpClientBuilder::default()
+        .build(format!("http://{}", addr))
+        .unwrap();
+    let created: serde_json::Value = client.request("create", rpc_params!["Ada"]).await.unwrap();
+    assert_eq!(created, serde_json::json!({ "id": 7, "name": "Ada" }));
+    let deleted: serde_json::Value = client.request("delete", rpc_params![7]).await.unwrap();
+    assert!(deleted.is_null());
+    let archived: serde_json::Value = client.request("archive", rpc_params![7]).await.unwrap();
+    assert!(archived.is_null());
+}
@@ -0,0 +1,73 @@
+//! The router of the generated REST server.
+
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::http::Request;
+use axum::http::StatusCode;
+use multi_rpc::prelude::*;
+use tokio::sync::RwLock;
+use tower::ServiceExt;
+
+#[multi_rpc_trait(protocols(rest_axum))]
+#[allow(async_fn_in_trait)]
+pub trait Greeter {
+    async fn greet(&self, name: String) -> String;
+}
+
+pub struct MyGreeter;
+
+#[multi_rpc_impl(protocols(rest_axum))]
+impl Greeter for MyGreeter {
+    #[rest(method = GET, path = "/greet/{name}")]
+    async fn greet(&self, name: String) -> String {
+        format!("Hello, {}!", name)
+    }
+}
+
+async fn status(router: &axum::Router, method: &str, uri: &str) -> StatusCode {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .body(Body::empty())
+        .unwrap();
+    router.clone().oneshot(request).await.unwrap().status()
+}
+
+#[tokio::test]
+async fn router_can_be_merged() {
+    let router = axum::Router::new()
+        .route("/health", axum::routing::get(|| async { "ok" }))
+        .fallback(|| async { (StatusCode::IM_A_TEAPOT, "not here") })
+        .merge(
+            greeter_for_my_greeter_impls::rest_axum_router()
+                .with_state(Arc::new(RwLock::new(MyGreeter))),
+        );
+
+    assert_eq!(status(&router, "GET", "/health").await, StatusCode::OK);
+    assert_eq!(status(&router, "GET", "/greet/Ada").await, StatusCode::OK);
+    assert_eq!(
+        status(&router, "GET", "/unknown").await,
+        StatusCode::IM_A_TEAPOT
+    );
+}
+
+#[tokio::test]
+async fn problem_fallbacks_answer_unknown_routes_and_methods() {
+    let router = multi_rpc::rest_axum::problem_fallbacks(
+        greeter_for_my_greeter_impls::rest_axum_router()
+            .with_state(Arc::new(RwLock::new(MyGreeter))),
+    );
+
+    let request = Request::get("/unknown").body(Body::empty()).unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(
+        response.headers()["content-type"],
+        multi_rpc::problem::CONTENT_TYPE
+    );
+    assert_eq!(
+        status(&router, "DELETE", "/greet/Ada").await,
+        StatusCode::METHOD_NOT_ALLOWED
+    );
+}
@@ -0,0 +1,159 @@
+//! `#[derive(RpcError)]`: the status, code and data of derived errors, over REST and JSON-RPC.
+
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::http::Request;
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::ClientError;
+use jsonrpsee::http_client::HttpClientBuilder;
+use jsonrpsee::rpc_params;
+use multi_rpc::error::ProtocolError;
+use multi_rpc::prelude::*;
+use multi_rpc_test_suite::wait_for_server;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use serde_json::Value;
+use tokio::sync::RwLock;
+use tower::ServiceExt;
+
+#[derive(Debug, Serialize, Deserialize, RpcError, thiserror::Error)]
+pub enum ShopError {
+    #[error("item {id} not found")]
+    #[rpc(status = 404, code = -32004, data)]
+    ItemNotFound { id: u32 },
+    #[error("only {0} left in stock")]
+    #[rpc(status = 409, code = -32009, data)]
+    OutOfStock(u32),
+    #[error("price {0} out of range {1}..{2}")]
+    #[rpc(status = 400, code = -32602, data)]
+    BadPrice(u32, u32, u32),
+    #[error("discontinued")]
+    #[rpc(status = 410)]
+    Discontinued { since: u32 },
+    #[error("database unavailable")]
+    Database,
+}
+
+#[derive(Debug, Serialize, Deserialize, RpcError, thiserror::Error)]
+#[error("forbidden")]
+#[rpc(status = 403, code = -32003)]
+pub struct Forbidden;
+
+#[test]
+fn attributes_choose_the_status_and_code() {
+    let cases = [
+        (ShopError::ItemNotFound { id: 7 }, 404, -32004),
+        (ShopError::OutOfStock(3), 409, -32009),
+        (ShopError::BadPrice(0, 1, 100), 400, -32602),
+        (ShopError::Discontinued { since: 2020 }, 410, -32603),
+        (ShopError::Database, 500, -32603),
+    ];
+    for (error, status, code) in cases {
+        assert_eq!(error.http_status(), status, "{error:?}");
+        assert_eq!(error.jsonrpc_code(), code, "{error:?}");
+    }
+    assert_eq!(Forbidden.http_status(), 403);
+    assert_eq!(Forbidden.jsonrpc_code(), -32003);
+}
+
+#[test]
+fn data_serializes_the_fields() {
+    assert_eq!(
+        ShopError::ItemNotFound { id: 7 }.data(),
+        Some(json!({ "id": 7 }))
+    );
+    assert_eq!(ShopError::OutOfStock(3).data(), Some(json!(3)));
+    assert_eq!(
+        ShopError::BadPrice(0, 1, 100).data(),
+        Some(json!([0, 1, 100]))
+    );
+    // Without `data`, the fields aren't sent.
+    assert_eq!(ShopError::Discontinued { since: 2020 }.data(), None);
+    assert_eq!(ShopError::Database.data(), None);
+    assert_eq!(Forbidden.data(), None);
+}
+
+#[multi_rpc_trait(protocols(rest_axum, jsonrpsee))]
+#[allow(async_fn_in_trait)]
+pub trait Shop {
+    async fn buy(&self, item: u32) -> Result<u32, ShopError>;
+}
+
+pub struct MyShop;
+
+#[multi_rpc_impl(protocols(rest_axum, jsonrpsee))]
+impl Shop for MyShop {
+    #[rest(method = POST, path = "/items/{item}/buy")]
+    async fn buy(&self, item: u32) -> Result<u32, ShopError> {
+        match item {
+            1 => Ok(1),
+            2 => Err(ShopError::OutOfStock(3)),
+            3 => Err(ShopError::BadPrice(0, 1, 100)),
+            _ => Err(ShopError::ItemNotFound { id: item }),
+        }
+    }
+}
+
+#[tokio::test]
+async fn rest_sends_data_as_problem_members() {
+    let router =
+        shop_for_my_shop_impls::rest_axum_router().with_state(Arc::new(RwLock::new(MyShop)));
+    let buy = |item: u32| {
+        let router = router.clone();
+        async move {
+            let request = Request::post(format!("/items/{item}/buy"))
+                .body(Body::empty())
+                .unwrap();
+            let response = router.oneshot(request).await.unwrap();
+            let status = response.status().as_u16();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            (status, serde_json::from_slice::<Value>(&body).unwrap())
+        }
+    };
+
+    let (status, problem) = buy(7).await;
+    assert_eq!(status, 404);
+    assert_eq!(problem["detail"], "item 7 not found");
+    assert_eq!(problem["id"], 7);
+
+    // Values other than objects are sent as `data`.
+    let (status, problem) = buy(2).await;
+    assert_eq!(status, 409);
+    assert_eq!(problem["data"], 3);
+    let (status, problem) = buy(3).await;
+    assert_eq!(status, 400);
+    assert_eq!(problem["data"], json!([0, 1, 100]));
+}
+
+#[tokio::test]
+async fn jsonrpc_sends_data_in_the_error_object() {
+    let addr = ([127, 0, 0, 1], 39201).into();
+    tokio::spawn(shop_for_my_shop_impls::jsonrpsee(addr)(Arc::new(
+        RwLock::new(MyShop),
+    )));
+    wait_for_server(addr).await;
+    let client = HttpClientBuilder::default()
+        .build(format!("http://{}", addr))
+        .unwrap();
+
+    let cases = [
+        (7, -32004, "item 7 not found", json!({ "id": 7 })),
+        (2, -32009, "only 3 left in stock", json!(3)),
+        (3, -32602, "price 0 out of range 1..100", json!([0, 1, 100])),
+    ];
+    for (item, code, message, data) in cases {
+        match client.request::<u32, _>("buy", rpc_params![item]).await {
+            Err(ClientError::Call(err)) => {
+                assert_eq!(err.code(), code);
+                assert_eq!(err.message(), message);
+                let sent: Value = serde_json::from_str(err.data().unwrap().get()).unwrap();
+                assert_eq!(sent, data);
+            }
+            other => panic!("expected an error for item {item}, got {:?}", other),
+        }
+    }
+}
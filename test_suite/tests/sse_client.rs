@@ -0,0 +1,67 @@
+//! The Server-Sent Events parser of the generated REST clients.
+
+use std::convert::Infallible;
+
+use axum::body::Body;
+use futures::StreamExt;
+use multi_rpc::client::rest::send_sse;
+use multi_rpc::client::rest::RestClient;
+use multi_rpc_test_suite::wait_for_server;
+
+// Serves `chunks` as the body of an event stream at `/events`.
+async fn serve_events(port: u16, chunks: &'static [&'static str]) -> RestClient {
+    let addr = ([127, 0, 0, 1], port).into();
+    let app = axum::Router::new().route(
+        "/events",
+        axum::routing::get(move || async move {
+            let chunks = futures::stream::iter(chunks).map(|chunk| Ok::<_, Infallible>(*chunk));
+            (
+                [("content-type", "text/event-stream")],
+                Body::from_stream(chunks),
+            )
+        }),
+    );
+    tokio::spawn(async move {
+        let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+        axum::serve(listener, app).await.unwrap();
+    });
+    wait_for_server(addr).await;
+    RestClient::new(format!("http://{}", addr))
+}
+
+async fn receive(client: &RestClient) -> Vec<u64> {
+    send_sse::<u64>(client.request("GET", "/events"))
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .collect()
+        .await
+}
+
+#[tokio::test]
+async fn events_separated_by_crlf() {
+    let client = serve_events(
+        39141,
+        &["data: 1\r\n\r\ndata: 2\r\n\r\n", ":keep-alive\r\n\r\n"],
+    )
+    .await;
+    assert_eq!(receive(&client).await, [1, 2]);
+}
+
+#[tokio::test]
+async fn events_split_across_chunks() {
+    let client = serve_events(
+        39142,
+        &[
+            "data: 1\r",
+            "\n\r",
+            "\ndata:",
+            " 2\n",
+            "\ndata: 3\r\r",
+            "data: 4\r\n",
+            "\r\n",
+        ],
+    )
+    .await;
+    assert_eq!(receive(&client).await, [1, 2, 3, 4]);
+}
@@ -0,0 +1,190 @@
+//! Server-streaming methods over every protocol.
+
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use axum::body::Body;
+use axum::http::Request;
+use futures::StreamExt;
+use jsonrpsee::ws_client::WsClientBuilder;
+use multi_rpc::prelude::*;
+use multi_rpc_test_suite::wait_for_server;
+use tokio::sync::RwLock;
+use tower::ServiceExt;
+
+#[multi_rpc_trait]
+#[allow(async_fn_in_trait)]
+pub trait Ticker {
+    async fn ticks(&self, count: u64) -> Streaming<u64>;
+    async fn forever(&self) -> Streaming<u64>;
+    async fn silent(&self) -> Streaming<u64>;
+}
+
+#[derive(Default)]
+pub struct MyTicker {
+    dropped: Arc<AtomicUsize>,
+}
+
+// Counts the streams of `forever` that were dropped.
+struct DropCounter(Arc<AtomicUsize>);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[multi_rpc_impl]
+impl Ticker for MyTicker {
+    #[rest(method = GET, path = "/ticks/{count}")]
+    async fn ticks(&self, count: u64) -> Streaming<u64> {
+        Streaming::new(futures::stream::iter(0..count))
+    }
+
+    #[rest(method = GET, path = "/forever")]
+    async fn forever(&self) -> Streaming<u64> {
+        let counter = DropCounter(self.dropped.clone());
+        Streaming::new(futures::stream::unfold(0, move |tick| {
+            let _counter = &counter;
+            async move {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+                Some((tick, tick + 1))
+            }
+        }))
+    }
+
+    // Never sends an item, so that every `next` waits.
+    #[rest(method = GET, path = "/silent")]
+    async fn silent(&self) -> Streaming<u64> {
+        let counter = DropCounter(self.dropped.clone());
+        Streaming::new(futures::stream::pending().inspect(move |_| {
+            let _counter = &counter;
+        }))
+    }
+}
+
+#[tokio::test]
+async fn tarpc_streams_every_item() {
+    let addr = ([127, 0, 0, 1], 39131).into();
+    let service = Arc::new(RwLock::new(MyTicker::default()));
+    tokio::spawn(ticker_for_my_ticker_impls::tarpc_tcp(addr)(service));
+    wait_for_server(addr).await;
+
+    let client = ticker_protocols::connect_tarpc(addr, multi_rpc::codec::TarpcCodec::Json)
+        .await
+        .unwrap();
+    let ticks: Vec<u64> = client
+        .ticks(tarpc::context::current(), 1000)
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .collect()
+        .await;
+    assert_eq!(ticks, (0..1000).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn tarpc_cancels_dropped_streams() {
+    let addr = ([127, 0, 0, 1], 39132).into();
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let service = Arc::new(RwLock::new(MyTicker {
+        dropped: dropped.clone(),
+    }));
+    tokio::spawn(ticker_for_my_ticker_impls::tarpc_tcp(addr)(service));
+    wait_for_server(addr).await;
+
+    let client = ticker_protocols::connect_tarpc(addr, multi_rpc::codec::TarpcCodec::Json)
+        .await
+        .unwrap();
+    let ticks = client.forever(tarpc::context::current()).await.unwrap();
+    let first: Vec<u64> = ticks.take(3).map(Result::unwrap).collect().await;
+    assert_eq!(first, [0, 1, 2]);
+
+    for _ in 0..100 {
+        if dropped.load(Ordering::SeqCst) == 1 {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("the server kept the stream after the client dropped it");
+}
+
+#[tokio::test]
+async fn tarpc_cancels_streams_while_waiting_for_items() {
+    let addr = ([127, 0, 0, 1], 39134).into();
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let service = Arc::new(RwLock::new(MyTicker {
+        dropped: dropped.clone(),
+    }));
+    tokio::spawn(ticker_for_my_ticker_impls::tarpc_tcp(addr)(service));
+    wait_for_server(addr).await;
+
+    let client = ticker_protocols::connect_tarpc(addr, multi_rpc::codec::TarpcCodec::Json)
+        .await
+        .unwrap();
+    // Calls the session methods directly, so that `cancel_silent` arrives while `next_silent`
+    // waits for items, for half its deadline of 60 seconds.
+    let session = client.open_silent(tarpc::context::current()).await.unwrap();
+    let next = tokio::spawn({
+        let client = client.clone();
+        let mut ctx = tarpc::context::current();
+        ctx.deadline = Instant::now() + Duration::from_secs(60);
+        async move { client.next_silent(ctx, session).await.unwrap() }
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    client
+        .cancel_silent(tarpc::context::current(), session)
+        .await
+        .unwrap();
+
+    let chunk = tokio::time::timeout(Duration::from_secs(1), next)
+        .await
+        .expect("`next` kept waiting after the cancel")
+        .unwrap();
+    assert!(chunk.items.is_empty());
+    assert!(chunk.end);
+
+    for _ in 0..100 {
+        if dropped.load(Ordering::SeqCst) == 1 {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("the server kept the stream while waiting for its items");
+}
+
+#[tokio::test]
+async fn rest_streams_server_sent_events() {
+    let router = ticker_for_my_ticker_impls::rest_axum_router()
+        .with_state(Arc::new(RwLock::new(MyTicker::default())));
+
+    let request = Request::get("/ticks/3").body(Body::empty()).unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "data: 0\n\ndata: 1\n\ndata: 2\n\n");
+}
+
+#[tokio::test]
+async fn jsonrpc_streams_subscriptions() {
+    use ticker_protocols::TickerRpcClient;
+
+    let addr = ([127, 0, 0, 1], 39133).into();
+    let service = Arc::new(RwLock::new(MyTicker::default()));
+    tokio::spawn(ticker_for_my_ticker_impls::jsonrpsee(addr)(service));
+    wait_for_server(addr).await;
+
+    let client = WsClientBuilder::default()
+        .build(format!("ws://{}", addr))
+        .await
+        .unwrap();
+    // JSON-RPC has no notification for the end of a subscription.
+    let subscription = client.ticks(3).await.unwrap();
+    let ticks: Vec<u64> = subscription.take(3).map(Result::unwrap).collect().await;
+    assert_eq!(ticks, [0, 1, 2]);
+}
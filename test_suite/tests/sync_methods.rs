@@ -0,0 +1,87 @@
+//! Sync methods, called inline or on the blocking thread pool with `#[blocking]`.
+
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::http::Request;
+use jsonrpsee::http_client::HttpClient;
+use multi_rpc::prelude::*;
+use multi_rpc_test_suite::wait_for_server;
+use tokio::sync::RwLock;
+use tower::ServiceExt;
+
+#[multi_rpc_trait]
+pub trait Calculator {
+    fn compute(&self, x: u64) -> u64;
+    fn compute_blocking(&self, x: u64) -> u64;
+}
+
+pub struct MyCalculator;
+
+#[multi_rpc_impl]
+impl Calculator for MyCalculator {
+    #[rest(method = GET, path = "/compute/{x}")]
+    fn compute(&self, x: u64) -> u64 {
+        x * 2
+    }
+
+    #[blocking]
+    #[rest(method = GET, path = "/compute-blocking/{x}")]
+    fn compute_blocking(&self, x: u64) -> u64 {
+        // Blocking on a future panics on the async worker threads, but not on the blocking pool.
+        tokio::runtime::Handle::current().block_on(async {});
+        x * 3
+    }
+}
+
+fn service() -> Arc<RwLock<MyCalculator>> {
+    Arc::new(RwLock::new(MyCalculator))
+}
+
+#[tokio::test]
+async fn sync_methods_are_served_over_tarpc() {
+    let addr = ([127, 0, 0, 1], 39121).into();
+    tokio::spawn(calculator_for_my_calculator_impls::tarpc_tcp(addr)(
+        service(),
+    ));
+    wait_for_server(addr).await;
+
+    let client = calculator_protocols::connect_tarpc(addr, multi_rpc::codec::TarpcCodec::Json)
+        .await
+        .unwrap();
+    let ctx = tarpc::context::current;
+    assert_eq!(client.compute(ctx(), 21).await.unwrap(), 42);
+    assert_eq!(client.compute_blocking(ctx(), 14).await.unwrap(), 42);
+}
+
+#[tokio::test]
+async fn sync_methods_are_served_over_rest() {
+    let router = calculator_for_my_calculator_impls::rest_axum_router().with_state(service());
+
+    for (uri, expected) in [("/compute/21", "42"), ("/compute-blocking/14", "42")] {
+        let request = Request::get(uri).body(Body::empty()).unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert!(response.status().is_success(), "{uri}");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, expected, "{uri}");
+    }
+}
+
+#[tokio::test]
+async fn sync_methods_are_served_over_jsonrpc() {
+    use calculator_protocols::CalculatorRpcClient;
+
+    let addr = ([127, 0, 0, 1], 39122).into();
+    tokio::spawn(calculator_for_my_calculator_impls::jsonrpsee(addr)(
+        service(),
+    ));
+    wait_for_server(addr).await;
+
+    let client = HttpClient::builder()
+        .build(format!("http://{}", addr))
+        .unwrap();
+    assert_eq!(client.compute(21).await.unwrap(), 42);
+    assert_eq!(client.compute_blocking(14).await.unwrap(), 42);
+}
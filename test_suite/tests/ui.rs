@@ -0,0 +1,43 @@
+//! Compile errors reported by the macros, checked against the `.stderr` files next to the cases.
+
+#[test]
+fn rest() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/rest/*.rs");
+}
+
+#[test]
+fn tarpc() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/tarpc/*.rs");
+}
+
+#[test]
+fn protocols() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/protocols/*.rs");
+}
+
+#[test]
+fn remote() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/remote/*.rs");
+}
+
+#[test]
+fn actor() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/actor/*.rs");
+}
+
+#[test]
+fn jsonrpc() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/jsonrpc/*.rs");
+}
+
+#[test]
+fn rpc_error() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/rpc_error/*.rs");
+}
@@ -0,0 +1,26 @@
+use multi_rpc::prelude::*;
+
+#[multi_rpc_trait(protocols(rest_axum))]
+pub trait Labels {
+    fn label(&self, id: u32) -> String;
+}
+
+pub trait Label {
+    type Text;
+}
+
+impl<T> Label for T {
+    type Text = String;
+}
+
+pub struct MyLabels<T>(T);
+
+#[multi_rpc_impl(protocols(rest_axum), actor)]
+impl<T: Send + Sync + 'static> Labels for MyLabels<T> {
+    #[rest(method = GET, path = "/labels/{id}")]
+    fn label(&self, id: u32) -> <T as Label>::Text {
+        id.to_string()
+    }
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use multi_rpc::prelude::*;
+
+#[multi_rpc_trait(protocols(jsonrpsee))]
+#[allow(async_fn_in_trait)]
+pub trait Greeter {
+    async fn greet(&self, name: String) -> String;
+    #[jsonrpc(name = "welcome", aliases = ["greet"])]
+    async fn welcome(&self, name: String) -> String;
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use multi_rpc::prelude::*;
+
+#[multi_rpc_trait(protocols(jsonrpsee))]
+#[jsonrpc(namespace = "ticker")]
+#[allow(async_fn_in_trait)]
+pub trait Ticker {
+    async fn ticks(&self, count: u64) -> Streaming<u64>;
+    async fn unsubscribe_ticks(&self) -> bool;
+}
+
+fn main() {}
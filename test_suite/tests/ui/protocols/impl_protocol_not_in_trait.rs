@@ -0,0 +1,19 @@
+use multi_rpc::prelude::*;
+
+#[multi_rpc_trait(protocols(tarpc))]
+#[allow(async_fn_in_trait)]
+pub trait Greeter {
+    async fn greet(&self, name: String) -> String;
+}
+
+#[derive(Clone)]
+pub struct MyGreeter;
+
+#[multi_rpc_impl(protocols(tarpc, jsonrpsee))]
+impl Greeter for MyGreeter {
+    async fn greet(&self, name: String) -> String {
+        format!("Hello, {name}!")
+    }
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use multi_rpc::prelude::*;
+
+#[multi_rpc_trait(protocols(tarpc, grpc))]
+#[allow(async_fn_in_trait)]
+pub trait Greeter {
+    async fn greet(&self, name: String) -> String;
+}
+
+fn main() {}
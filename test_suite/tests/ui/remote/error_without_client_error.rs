@@ -0,0 +1,14 @@
+use multi_rpc::prelude::*;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CalculatorError;
+
+#[multi_rpc_trait(protocols(jsonrpsee), remote)]
+#[allow(async_fn_in_trait)]
+pub trait Calculator {
+    async fn compute(&self, x: u64) -> Result<u64, CalculatorError>;
+}
+
+fn main() {}
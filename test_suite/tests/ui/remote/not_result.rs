@@ -0,0 +1,10 @@
+use multi_rpc::prelude::*;
+
+#[multi_rpc_trait(protocols(jsonrpsee), remote)]
+#[allow(async_fn_in_trait)]
+pub trait Calculator {
+    async fn compute(&self, x: u64) -> u64;
+    async fn reset(&self);
+}
+
+fn main() {}
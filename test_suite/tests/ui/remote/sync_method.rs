@@ -0,0 +1,8 @@
+use multi_rpc::prelude::*;
+
+#[multi_rpc_trait(protocols(jsonrpsee), remote)]
+pub trait Calculator {
+    fn compute(&self, x: u64) -> u64;
+}
+
+fn main() {}
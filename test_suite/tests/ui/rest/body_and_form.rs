@@ -0,0 +1,17 @@
+use multi_rpc::prelude::*;
+
+#[multi_rpc_trait(protocols(rest_axum))]
+#[allow(async_fn_in_trait)]
+pub trait Users {
+    async fn update_user(&self, id: u64, name: String, email: String);
+}
+
+pub struct Service;
+
+#[multi_rpc_impl(protocols(rest_axum))]
+impl Users for Service {
+    #[rest(method = PUT, path = "/users/{id}", body(name), form(email))]
+    async fn update_user(&self, id: u64, name: String, email: String) {}
+}
+
+fn main() {}
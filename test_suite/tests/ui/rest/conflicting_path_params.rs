@@ -0,0 +1,23 @@
+use multi_rpc::prelude::*;
+
+#[multi_rpc_trait(protocols(rest_axum))]
+#[allow(async_fn_in_trait)]
+pub trait Users {
+    async fn get_user(&self, id: u64) -> String;
+    async fn delete_user(&self, user_id: u64);
+}
+
+pub struct Service;
+
+#[multi_rpc_impl(protocols(rest_axum))]
+impl Users for Service {
+    #[rest(method = GET, path = "/users/{id}")]
+    async fn get_user(&self, id: u64) -> String {
+        id.to_string()
+    }
+
+    #[rest(method = DELETE, path = "/users/{user_id}")]
+    async fn delete_user(&self, user_id: u64) {}
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use multi_rpc::prelude::*;
+
+#[multi_rpc_trait(protocols(rest_axum))]
+#[allow(async_fn_in_trait)]
+pub trait Users {
+    async fn create_user(&self, name: String) -> u64;
+}
+
+pub struct Service;
+
+#[multi_rpc_impl(protocols(rest_axum))]
+impl Users for Service {
+    #[rest(method = POST, path = "/users", body(name), status = 600)]
+    async fn create_user(&self, name: String) -> u64 {
+        name.len() as u64
+    }
+}
+
+fn main() {}
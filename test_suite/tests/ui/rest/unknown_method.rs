@@ -0,0 +1,19 @@
+use multi_rpc::prelude::*;
+
+#[multi_rpc_trait(protocols(rest_axum))]
+#[allow(async_fn_in_trait)]
+pub trait Users {
+    async fn get_user(&self, id: u64) -> String;
+}
+
+pub struct Service;
+
+#[multi_rpc_impl(protocols(rest_axum))]
+impl Users for Service {
+    #[rest(method = FETCH, path = "/users/{id}")]
+    async fn get_user(&self, id: u64) -> String {
+        id.to_string()
+    }
+}
+
+fn main() {}
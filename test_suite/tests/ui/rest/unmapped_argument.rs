@@ -0,0 +1,19 @@
+use multi_rpc::prelude::*;
+
+#[multi_rpc_trait(protocols(rest_axum))]
+#[allow(async_fn_in_trait)]
+pub trait Users {
+    async fn find_users(&self, name: String, limit: u32) -> Vec<u64>;
+}
+
+pub struct Service;
+
+#[multi_rpc_impl(protocols(rest_axum))]
+impl Users for Service {
+    #[rest(method = GET, path = "/users", query(name))]
+    async fn find_users(&self, name: String, limit: u32) -> Vec<u64> {
+        vec![name.len() as u64; limit as usize]
+    }
+}
+
+fn main() {}
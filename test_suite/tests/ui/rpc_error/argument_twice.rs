@@ -0,0 +1,9 @@
+use multi_rpc::prelude::*;
+
+#[derive(Debug, RpcError)]
+pub enum ShopError {
+    #[rpc(code = -32004, code = -32005)]
+    NotFound,
+}
+
+fn main() {}
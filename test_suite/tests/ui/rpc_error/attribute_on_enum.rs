@@ -0,0 +1,9 @@
+use multi_rpc::prelude::*;
+
+#[derive(Debug, RpcError)]
+#[rpc(status = 404)]
+pub enum ShopError {
+    NotFound,
+}
+
+fn main() {}
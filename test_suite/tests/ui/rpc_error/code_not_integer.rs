@@ -0,0 +1,9 @@
+use multi_rpc::prelude::*;
+
+#[derive(Debug, RpcError)]
+pub enum ShopError {
+    #[rpc(status = 404, code = "not found")]
+    NotFound,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use multi_rpc::prelude::*;
+
+#[derive(Debug, RpcError)]
+pub enum ShopError {
+    #[rpc(status = 404, data = true)]
+    NotFound { id: u32 },
+}
+
+fn main() {}
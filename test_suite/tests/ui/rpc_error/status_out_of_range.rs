@@ -0,0 +1,9 @@
+use multi_rpc::prelude::*;
+
+#[derive(Debug, RpcError)]
+pub enum ShopError {
+    #[rpc(status = 42)]
+    NotFound,
+}
+
+fn main() {}
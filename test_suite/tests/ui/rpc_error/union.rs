@@ -0,0 +1,8 @@
+use multi_rpc::prelude::*;
+
+#[derive(RpcError)]
+pub union ShopError {
+    code: u32,
+}
+
+fn main() {}
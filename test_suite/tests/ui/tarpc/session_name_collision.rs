@@ -0,0 +1,10 @@
+use multi_rpc::prelude::*;
+
+#[multi_rpc_trait(protocols(tarpc))]
+#[allow(async_fn_in_trait)]
+pub trait Ticker {
+    async fn ticks(&self, count: u64) -> Streaming<u64>;
+    async fn next_ticks(&self) -> u64;
+}
+
+fn main() {}